@@ -0,0 +1,85 @@
+use nalgebra_glm::dot;
+use std::f32::consts::PI;
+
+use crate::color::LinearColor;
+use crate::fragment::Fragment;
+use crate::noise::smoothstep;
+use crate::shaders::FragmentShader;
+use crate::Uniforms;
+
+// Capa de atmósfera de un planeta: una esfera un poco más grande, dibujada transparente
+// sobre la superficie
+pub struct Atmosphere {
+    pub scale: f32,  // radio de la capa relativo al planeta (1.05 = 5% más grande)
+    pub shader: AtmosphereShader,
+}
+
+impl Atmosphere {
+    pub fn new(scale: f32, rayleigh: LinearColor, mie: LinearColor, density: f32) -> Self {
+        Atmosphere {
+            scale,
+            shader: AtmosphereShader {
+                rayleigh,
+                mie,
+                mie_g: 0.76,
+                density,
+                planet_ratio: 1.0 / scale,
+            },
+        }
+    }
+}
+
+// Dispersión aproximada tipo Rayleigh/Mie. La opacidad sale del largo del rayo de vista
+// dentro de la capa (máximo en el borde del planeta), y el color de las funciones de fase
+// según el ángulo entre la vista y el sol.
+pub struct AtmosphereShader {
+    pub rayleigh: LinearColor,  // color de la dispersión por moléculas (azul en la Tierra)
+    pub mie: LinearColor,       // color del halo de partículas, concentrado hacia el sol
+    pub mie_g: f32,             // anisotropía de Henyey-Greenstein para Mie (0 = isótropo)
+    pub density: f32,           // opacidad por unidad de grosor de la capa
+    pub planet_ratio: f32,      // radio del planeta / radio de la capa
+}
+
+impl AtmosphereShader {
+    // Largo del rayo de vista dentro de la capa, en unidades de su grosor.
+    // `impact` es la distancia del rayo al centro, relativa al radio de la capa.
+    fn path_length(&self, impact: f32) -> f32 {
+        let rp = self.planet_ratio;
+        let outer = (1.0 - impact * impact).max(0.0).sqrt();
+        let path = if impact >= rp {
+            // El rayo pasa por encima del planeta: atraviesa la capa completa
+            2.0 * outer
+        } else {
+            // El rayo choca con la superficie: solo cuenta el tramo hasta el suelo
+            outer - (rp * rp - impact * impact).max(0.0).sqrt()
+        };
+        path / (1.0 - rp)
+    }
+}
+
+impl FragmentShader for AtmosphereShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let normal = fragment.normal;
+        let to_camera = (uniforms.camera_position - fragment.world_position).normalize();
+        let to_light = (uniforms.sun_position - fragment.world_position).normalize();
+
+        // Opacidad por el camino recorrido dentro de la capa
+        let mu = dot(&normal, &to_camera).clamp(0.0, 1.0);
+        let impact = (1.0 - mu * mu).sqrt();
+        let optical_depth = self.density * self.path_length(impact);
+        let opacity = 1.0 - (-optical_depth).exp();
+
+        // Lado iluminado, con una franja de crepúsculo más allá del terminador
+        let n_dot_l = dot(&normal, &to_light);
+        let daylight = smoothstep(-0.25, 0.25, n_dot_l);
+
+        // Funciones de fase; cos_theta = 1 cuando la luz del sol viaja hacia la cámara
+        let cos_theta = dot(&-to_light, &to_camera);
+        let rayleigh_phase = 0.75 * (1.0 + cos_theta * cos_theta);
+        let g = self.mie_g;
+        let mie_phase = (1.0 - g * g) / (4.0 * PI * (1.0 + g * g - 2.0 * g * cos_theta).powf(1.5));
+
+        let color = self.rayleigh * rayleigh_phase + self.mie * mie_phase;
+        color.with_alpha((opacity * daylight).clamp(0.0, 1.0))
+    }
+}
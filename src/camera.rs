@@ -48,7 +48,7 @@ impl Camera {
     
     // Zoom in/out
     pub fn zoom(&mut self, delta: f32) {
-        self.radius = (self.radius + delta).max(50.0).min(800.0);
+        self.radius = (self.radius + delta).clamp(50.0, 800.0);
         self.eye.x = self.center.x + self.radius * self.angle.cos();
         self.eye.y = self.center.y + self.radius * self.angle.sin();
         self.has_changed = true;
@@ -0,0 +1,212 @@
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use crate::vertex::Vertex;
+
+// Planos del frustum en espacio de clip: un punto está dentro si dot(plano, clip) >= 0
+const FRUSTUM_PLANES: [[f32; 4]; 6] = [
+    [0.0, 0.0, 1.0, 1.0],   // near:   z >= -w
+    [0.0, 0.0, -1.0, 1.0],  // far:    z <= w
+    [1.0, 0.0, 0.0, 1.0],   // left:   x >= -w
+    [-1.0, 0.0, 0.0, 1.0],  // right:  x <= w
+    [0.0, 1.0, 0.0, 1.0],   // bottom: y >= -w
+    [0.0, -1.0, 0.0, 1.0],  // top:    y <= w
+];
+
+fn plane_distance(plane: &[f32; 4], vertex: &Vertex) -> f32 {
+    Vec4::new(plane[0], plane[1], plane[2], plane[3]).dot(&vertex.clip_position)
+}
+
+// Resultado de recortar un triángulo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Clip {
+    Outside,  // completamente fuera: se descarta
+    Inside,   // completamente dentro: se usa tal cual, sin copiar los vértices
+    Clipped,  // cruza algún plano: el polígono recortado queda en `polygon`
+}
+
+// Una caja (en espacio de objeto) queda fuera del frustum si sus ocho esquinas están del
+// lado de afuera de un mismo plano. `model_view_projection` la lleva a espacio de clip.
+pub fn box_outside_frustum(min: &Vec3, max: &Vec3, model_view_projection: &Mat4) -> bool {
+    let corners = [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+        let corner = Vec4::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+            1.0,
+        );
+        model_view_projection * corner
+    });
+
+    FRUSTUM_PLANES.iter().any(|plane| {
+        let plane = Vec4::new(plane[0], plane[1], plane[2], plane[3]);
+        corners.iter().all(|corner| plane.dot(corner) < 0.0)
+    })
+}
+
+// Recorta un triángulo contra los seis planos del frustum (Sutherland-Hodgman en
+// coordenadas homogéneas). Si lo recorta, deja en `polygon` un polígono convexo listo
+// para triangular en abanico. `polygon` y `scratch` se reutilizan entre llamadas: cada
+// plano escribe en el otro buffer y se intercambian, sin reservar memoria por triángulo.
+pub fn clip_triangle(
+    v1: &Vertex,
+    v2: &Vertex,
+    v3: &Vertex,
+    polygon: &mut Vec<Vertex>,
+    scratch: &mut Vec<Vertex>,
+) -> Clip {
+    polygon.clear();
+    let mut fully_inside = true;
+
+    for plane in &FRUSTUM_PLANES {
+        let d1 = plane_distance(plane, v1);
+        let d2 = plane_distance(plane, v2);
+        let d3 = plane_distance(plane, v3);
+
+        // Los tres vértices fuera del mismo plano: descartar sin recortar
+        if d1 < 0.0 && d2 < 0.0 && d3 < 0.0 {
+            return Clip::Outside;
+        }
+        if d1 < 0.0 || d2 < 0.0 || d3 < 0.0 {
+            fully_inside = false;
+        }
+    }
+
+    if fully_inside {
+        return Clip::Inside;
+    }
+
+    polygon.extend([v1.clone(), v2.clone(), v3.clone()]);
+    for plane in &FRUSTUM_PLANES {
+        clip_polygon_against_plane(polygon, plane, scratch);
+        std::mem::swap(polygon, scratch);
+        if polygon.len() < 3 {
+            polygon.clear();
+            return Clip::Outside;
+        }
+    }
+    Clip::Clipped
+}
+
+// Deja en `output` la parte de `polygon` del lado de adentro del plano
+fn clip_polygon_against_plane(polygon: &[Vertex], plane: &[f32; 4], output: &mut Vec<Vertex>) {
+    output.clear();
+
+    for i in 0..polygon.len() {
+        let current = &polygon[i];
+        let next = &polygon[(i + 1) % polygon.len()];
+
+        let d_current = plane_distance(plane, current);
+        let d_next = plane_distance(plane, next);
+
+        if d_current >= 0.0 {
+            output.push(current.clone());
+        }
+
+        // La arista cruza el plano: agregar el punto de intersección
+        if (d_current >= 0.0) != (d_next >= 0.0) {
+            let t = d_current / (d_current - d_next);
+            output.push(current.lerp(next, t));
+        }
+    }
+}
+
+// Recorta un segmento contra el frustum (Liang-Barsky en coordenadas homogéneas).
+// Devuelve None si el segmento queda completamente fuera.
+pub fn clip_line(a: &Vertex, b: &Vertex) -> Option<(Vertex, Vertex)> {
+    let mut t_start: f32 = 0.0;
+    let mut t_end: f32 = 1.0;
+
+    for plane in &FRUSTUM_PLANES {
+        let d_a = plane_distance(plane, a);
+        let d_b = plane_distance(plane, b);
+
+        if d_a < 0.0 && d_b < 0.0 {
+            return None;
+        }
+
+        if d_a < 0.0 {
+            t_start = t_start.max(d_a / (d_a - d_b));
+        } else if d_b < 0.0 {
+            t_end = t_end.min(d_a / (d_a - d_b));
+        }
+
+        if t_start > t_end {
+            return None;
+        }
+    }
+
+    let start = if t_start > 0.0 { a.lerp(b, t_start) } else { a.clone() };
+    let end = if t_end < 1.0 { a.lerp(b, t_end) } else { b.clone() };
+
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec2;
+
+    // Vértice con la posición de clip dada; el resto de los atributos no importa
+    fn clip_vertex(x: f32, y: f32, z: f32, w: f32) -> Vertex {
+        let mut vertex = Vertex::new(Vec3::new(x, y, z), Vec3::new(0.0, 0.0, 1.0), Vec2::new(0.0, 0.0));
+        vertex.clip_position = Vec4::new(x, y, z, w);
+        vertex
+    }
+
+    fn inside_frustum(vertex: &Vertex) -> bool {
+        FRUSTUM_PLANES.iter().all(|plane| plane_distance(plane, vertex) >= -1e-5)
+    }
+
+    #[test]
+    fn triangle_fully_inside_is_kept_as_is() {
+        let (mut polygon, mut scratch) = (Vec::new(), Vec::new());
+        let a = clip_vertex(0.0, 0.0, 0.0, 1.0);
+        let b = clip_vertex(0.5, 0.0, 0.0, 1.0);
+        let c = clip_vertex(0.0, 0.5, 0.0, 1.0);
+
+        assert_eq!(clip_triangle(&a, &b, &c, &mut polygon, &mut scratch), Clip::Inside);
+        assert!(polygon.is_empty());
+    }
+
+    #[test]
+    fn triangle_fully_outside_is_discarded() {
+        let (mut polygon, mut scratch) = (Vec::new(), Vec::new());
+        let a = clip_vertex(2.0, 0.0, 0.0, 1.0);
+        let b = clip_vertex(3.0, 0.0, 0.0, 1.0);
+        let c = clip_vertex(2.0, 0.5, 0.0, 1.0);
+
+        assert_eq!(clip_triangle(&a, &b, &c, &mut polygon, &mut scratch), Clip::Outside);
+        assert!(polygon.is_empty());
+    }
+
+    #[test]
+    fn vertex_behind_near_plane_becomes_quad() {
+        let (mut polygon, mut scratch) = (Vec::new(), Vec::new());
+        let a = clip_vertex(0.0, 0.0, 0.0, 1.0);
+        let b = clip_vertex(0.5, 0.0, 0.0, 1.0);
+        let behind = clip_vertex(0.0, 0.0, -2.0, -1.0);
+
+        assert_eq!(clip_triangle(&a, &b, &behind, &mut polygon, &mut scratch), Clip::Clipped);
+        assert_eq!(polygon.len(), 4);
+        assert!(polygon.iter().all(inside_frustum));
+        assert!(polygon.iter().all(|vertex| vertex.clip_position.w > 0.0));
+    }
+
+    #[test]
+    fn line_crossing_w_zero_is_cut_at_near_plane() {
+        let a = clip_vertex(0.0, 0.0, 0.0, 1.0);
+        let b = clip_vertex(0.0, 0.0, -2.0, -1.0);
+
+        let (start, end) = clip_line(&a, &b).expect("la parte delante de la cámara es visible");
+        assert_eq!(start.clip_position, a.clip_position);
+        assert!(end.clip_position.w > 0.0);
+        assert!((end.clip_position - Vec4::new(0.0, 0.0, -0.5, 0.5)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn line_fully_outside_is_discarded() {
+        let a = clip_vertex(2.0, 0.0, 0.0, 1.0);
+        let b = clip_vertex(3.0, 1.0, 0.0, 1.0);
+
+        assert!(clip_line(&a, &b).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use nalgebra_glm::{dot, Vec3};
+
+use crate::color::LinearColor;
+use crate::fragment::Fragment;
+use crate::noise::{fbm3, smoothstep};
+use crate::shaders::FragmentShader;
+use crate::shadows::sun_visibility;
+use crate::sphere::equirect_uv;
+use crate::texture::Texture;
+use crate::Uniforms;
+
+// Capa de nubes de un planeta: una esfera un poco más grande que gira sobre el eje Y
+// a su propio ritmo, dibujada transparente entre la superficie y la atmósfera
+pub struct CloudLayer {
+    pub scale: f32,           // radio de la capa relativo al planeta (1.02 = 2% más grande)
+    pub rotation_speed: f32,  // radianes por frame, independiente de la rotación del planeta
+    pub rotation: f32,        // ángulo actual de la capa
+    pub shader: CloudShader,
+}
+
+impl CloudLayer {
+    pub fn new(scale: f32, rotation_speed: f32, shader: CloudShader) -> Self {
+        CloudLayer {
+            scale,
+            rotation_speed,
+            rotation: 0.0,
+            shader,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.rotation += self.rotation_speed;
+    }
+}
+
+// De dónde sale la cobertura de nubes
+pub enum CloudMap {
+    Texture(Texture),   // mapa equirectangular: blanco = nublado, negro = despejado
+    Noise(CloudNoise),  // nubes procedurales que cambian lentamente con el tiempo
+}
+
+pub struct CloudNoise {
+    pub coverage: f32,   // fracción aproximada del cielo cubierta (0..1)
+    pub frequency: f32,  // tamaño de los sistemas de nubes: más alto = más chicos
+    pub evolution: f32,  // velocidad con la que se deforman, por segundo
+}
+
+// Nubes blancas iluminadas por el sol. La opacidad es la cobertura por `opacity`; la
+// misma cobertura, vista desde la superficie hacia el sol, da la sombra de las nubes.
+pub struct CloudShader {
+    pub map: CloudMap,
+    pub color: LinearColor,
+    pub opacity: f32,
+}
+
+impl CloudShader {
+    // Cobertura (0..1) en una dirección desde el centro, en espacio de objeto de la capa
+    fn coverage(&self, direction: Vec3, time: f32) -> f32 {
+        match &self.map {
+            CloudMap::Texture(texture) => texture.sample(equirect_uv(direction)).r.clamp(0.0, 1.0),
+            CloudMap::Noise(noise) => {
+                // Deformar el dominio con otro ruido: las nubes se arremolinan en vez de deslizarse
+                let drift = time * noise.evolution;
+                let p = direction * noise.frequency;
+                let warp = Vec3::new(
+                    fbm3(p + Vec3::new(drift, 4.2, 1.7), 3),
+                    fbm3(p + Vec3::new(8.3, drift, 2.8), 3),
+                    fbm3(p + Vec3::new(1.2, 6.1, drift), 3),
+                );
+                let density = fbm3(p + warp * 1.2, 5);
+
+                let threshold = 0.7 - 0.3 * noise.coverage;
+                smoothstep(threshold, threshold + 0.12, density)
+            }
+        }
+    }
+
+    // Fracción de luz del sol que atraviesa la capa hasta `point` (1 = cielo despejado)
+    pub fn transmittance(&self, shadow: &CloudShadow, point: &Vec3, sun_position: &Vec3, time: f32) -> f32 {
+        // Punto donde el rayo hacia el sol sale de la capa
+        let to_light = (sun_position - point).normalize();
+        let offset = point - shadow.center;
+        let b = dot(&offset, &to_light);
+        let c = offset.magnitude_squared() - shadow.radius * shadow.radius;
+        let t = -b + (b * b - c).max(0.0).sqrt();
+        let hit = (offset + to_light * t).normalize();
+
+        // Deshacer la inclinación del planeta y el giro de la capa para pasar a su espacio de objeto
+        let (sin, cos) = shadow.tilt.sin_cos();
+        let hit = Vec3::new(hit.x, cos * hit.y + sin * hit.z, cos * hit.z - sin * hit.y);
+        let (sin, cos) = shadow.rotation.sin_cos();
+        let direction = Vec3::new(cos * hit.x - sin * hit.z, hit.y, sin * hit.x + cos * hit.z);
+
+        1.0 - self.coverage(direction, time) * self.opacity
+    }
+}
+
+impl FragmentShader for CloudShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let alpha = self.coverage(fragment.object_position.normalize(), uniforms.time) * self.opacity;
+        if alpha <= 0.0 {
+            return LinearColor::black().with_alpha(0.0);
+        }
+
+        let normal = fragment.normal;
+        let to_light = (uniforms.sun_position - fragment.world_position).normalize();
+
+        // La luz se dispersa dentro de la nube: ilumina un poco más allá del terminador
+        let n_dot_l = ((dot(&normal, &to_light) + 0.15) / 1.15).clamp(0.0, 1.0);
+
+        let visibility = if uniforms.shadows && n_dot_l > 0.0 {
+            sun_visibility(
+                &fragment.world_position,
+                &uniforms.sun_position,
+                uniforms.sun_radius,
+                uniforms.occluders,
+            )
+        } else {
+            1.0
+        };
+
+        // Las nubes del lado nocturno quedan oscuras y tapan lo que hay debajo
+        let light = 0.02 + 0.9 * n_dot_l * visibility;
+        (self.color * light).with_alpha(alpha.clamp(0.0, 1.0))
+    }
+}
+
+// Capa de nubes que proyecta sombra sobre la superficie del planeta que se está dibujando
+#[derive(Clone, Copy)]
+pub struct CloudShadow<'a> {
+    pub shader: &'a CloudShader,
+    pub center: Vec3,
+    pub radius: f32,    // radio de la capa en espacio mundial
+    pub tilt: f32,      // inclinación del eje del planeta alrededor de X
+    pub rotation: f32,  // giro de la capa sobre el eje Y
+}
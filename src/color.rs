@@ -18,15 +18,6 @@ impl Color {
     Color { r: 0, g: 0, b: 0 }
   }
 
-  // New constructor to initialize the color using r, g, b values as f32 (0.0 to 1.0)
-  pub fn from_float(r: f32, g: f32, b: f32) -> Self {
-    Color {
-      r: (r.clamp(0.0, 1.0) * 255.0) as u8,
-      g: (g.clamp(0.0, 1.0) * 255.0) as u8,
-      b: (b.clamp(0.0, 1.0) * 255.0) as u8,
-    }
-  }
-
   // Function to create a color from a hex value
   pub fn from_hex(hex: u32) -> Self {
     let r = ((hex >> 16) & 0xFF) as u8;
@@ -34,11 +25,6 @@ impl Color {
     let b = (hex & 0xFF) as u8;
     Color { r, g, b }
   }
-
-  // Function to return the color as a hex value
-  pub fn to_hex(&self) -> u32 {
-    ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
-  }
 }
 
 // Implement addition for Color
@@ -76,4 +62,150 @@ impl fmt::Display for Color {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
   }
-}
\ No newline at end of file
+}
+// Color lineal en punto flotante para el pipeline HDR: los canales pueden superar 1.0
+// (el sol, reflejos especulares) y el tone mapping los lleva al rango de pantalla al final.
+// `a` es la opacidad (no premultiplicada) que usan los modos de mezcla del rasterizador.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearColor {
+  pub r: f32,
+  pub g: f32,
+  pub b: f32,
+  pub a: f32,
+}
+
+impl LinearColor {
+  pub fn new(r: f32, g: f32, b: f32) -> Self {
+    LinearColor { r, g, b, a: 1.0 }
+  }
+
+  pub fn black() -> Self {
+    LinearColor::new(0.0, 0.0, 0.0)
+  }
+
+  pub fn with_alpha(self, a: f32) -> Self {
+    LinearColor { a, ..self }
+  }
+
+  // Convertir un color sRGB de 8 bits (colores hex, texturas) a espacio lineal
+  pub fn from_srgb(color: Color) -> Self {
+    LinearColor::new(
+      srgb_to_linear(color.r as f32 / 255.0),
+      srgb_to_linear(color.g as f32 / 255.0),
+      srgb_to_linear(color.b as f32 / 255.0),
+    )
+  }
+
+  pub fn from_hex(hex: u32) -> Self {
+    LinearColor::from_srgb(Color::from_hex(hex))
+  }
+
+  // Codificar a sRGB de 8 bits (0x00RRGGBB); los canales se recortan a [0, 1]
+  pub fn to_srgb_hex(self) -> u32 {
+    let encode = |c: f32| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0 + 0.5) as u32;
+    (encode(self.r) << 16) | (encode(self.g) << 8) | encode(self.b)
+  }
+
+  // Luminancia relativa (Rec. 709)
+  pub fn luminance(&self) -> f32 {
+    0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+  }
+
+  pub fn is_black(&self) -> bool {
+    self.r <= 0.0 && self.g <= 0.0 && self.b <= 0.0
+  }
+
+  pub fn lerp(&self, other: &LinearColor, t: f32) -> LinearColor {
+    LinearColor {
+      r: self.r + (other.r - self.r) * t,
+      g: self.g + (other.g - self.g) * t,
+      b: self.b + (other.b - self.b) * t,
+      a: self.a + (other.a - self.a) * t,
+    }
+  }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+  if c <= 0.0031308 {
+    c * 12.92
+  } else {
+    1.055 * c.powf(1.0 / 2.4) - 0.055
+  }
+}
+
+impl Add for LinearColor {
+  type Output = LinearColor;
+
+  // Suma de luz: la opacidad es la del color de la izquierda
+  fn add(self, other: LinearColor) -> LinearColor {
+    LinearColor {
+      r: self.r + other.r,
+      g: self.g + other.g,
+      b: self.b + other.b,
+      a: self.a,
+    }
+  }
+}
+
+impl Mul<f32> for LinearColor {
+  type Output = LinearColor;
+
+  // Escala la intensidad sin cambiar la opacidad
+  fn mul(self, scalar: f32) -> LinearColor {
+    LinearColor {
+      r: self.r * scalar,
+      g: self.g * scalar,
+      b: self.b * scalar,
+      a: self.a,
+    }
+  }
+}
+
+// Multiplicación componente a componente (luz coloreada sobre un albedo), incluida la opacidad
+impl Mul<LinearColor> for LinearColor {
+  type Output = LinearColor;
+
+  fn mul(self, other: LinearColor) -> LinearColor {
+    LinearColor {
+      r: self.r * other.r,
+      g: self.g * other.g,
+      b: self.b * other.b,
+      a: self.a * other.a,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn srgb_round_trips_through_linear() {
+    for value in 0..=255u32 {
+      let hex = (value << 16) | ((255 - value) << 8) | (value / 2);
+      assert_eq!(LinearColor::from_hex(hex).to_srgb_hex(), hex, "0x{:06X}", hex);
+    }
+  }
+
+  #[test]
+  fn srgb_encoding_clamps_out_of_range_channels() {
+    assert_eq!(LinearColor::new(-0.5, 0.0, 0.0).to_srgb_hex(), 0x000000);
+    assert_eq!(LinearColor::new(1.0, 4.0, 100.0).to_srgb_hex(), 0xFFFFFF);
+  }
+
+  #[test]
+  fn srgb_decoding_matches_reference_values() {
+    let mid = LinearColor::from_srgb(Color::from_hex(0x808080));
+    assert!((mid.r - 0.2158605).abs() < 1e-5);
+    assert_eq!(LinearColor::from_hex(0x000000).r, 0.0);
+    assert!((LinearColor::from_hex(0xFFFFFF).r - 1.0).abs() < 1e-6);
+  }
+}
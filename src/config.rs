@@ -0,0 +1,201 @@
+use std::env;
+use std::str::FromStr;
+
+use crate::framebuffer::Antialiasing;
+use crate::postprocess::BloomSettings;
+use crate::sphere::SphereMesh;
+use crate::texture::FilterMode;
+use crate::tonemap::ToneMapping;
+
+// Opciones de renderizado de cada frame; algunas se pueden cambiar con la ventana abierta
+#[derive(Clone, Copy, Debug)]
+pub struct RenderSettings {
+    pub shadows: bool,  // sombras entre cuerpos (eclipses)
+    pub tone_mapping: ToneMapping,
+    pub exposure: f32,  // multiplicador del color HDR antes del tone mapping
+    pub post_processing: bool,  // aplicar la cadena de post-procesado (bloom)
+    pub orbit_thickness: f32,  // grosor de las líneas de las órbitas, en píxeles
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            shadows: true,
+            tone_mapping: ToneMapping::Aces,
+            exposure: 1.0,
+            post_processing: true,
+            orbit_thickness: 1.0,
+        }
+    }
+}
+
+// Opciones de ejecución leídas desde la línea de comandos
+pub struct Config {
+    pub help: bool,
+    pub headless: bool,
+    pub bench: bool,
+    pub width: usize,
+    pub height: usize,
+    pub frames: usize,
+    pub frame: Option<usize>,
+    pub output: String,
+    pub texture_filter: FilterMode,
+    pub sphere_mesh: SphereMesh,
+    pub render: RenderSettings,
+    pub antialiasing: Antialiasing,
+    pub bloom_settings: BloomSettings,
+    pub milky_way: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            help: false,
+            headless: false,
+            bench: false,
+            width: 1200,
+            height: 800,
+            frames: 1,
+            frame: None,
+            output: String::from("frame.png"),
+            texture_filter: FilterMode::Bilinear,
+            sphere_mesh: SphereMesh::Ico,
+            render: RenderSettings::default(),
+            antialiasing: Antialiasing::default(),
+            bloom_settings: BloomSettings::default(),
+            milky_way: true,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_args() -> Result<Self, String> {
+        let mut config = Config::default();
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--help" | "-h" => config.help = true,
+                "--headless" => config.headless = true,
+                "--bench" => config.bench = true,
+                "--no-shadows" => config.render.shadows = false,
+                "--width" => config.width = parse_value(&arg, args.next())?,
+                "--height" => config.height = parse_value(&arg, args.next())?,
+                "--frames" => config.frames = parse_value(&arg, args.next())?,
+                "--frame" => config.frame = Some(parse_value(&arg, args.next())?),
+                "--output" | "-o" => {
+                    config.output = args.next().ok_or_else(|| format!("Falta el valor para {}", arg))?;
+                }
+                "--texture-filter" => {
+                    config.texture_filter = match args.next().as_deref() {
+                        Some("nearest") => FilterMode::Nearest,
+                        Some("bilinear") => FilterMode::Bilinear,
+                        _ => return Err(String::from("--texture-filter espera nearest o bilinear")),
+                    };
+                }
+                "--sphere" => {
+                    config.sphere_mesh = match args.next().as_deref() {
+                        Some("uv") => SphereMesh::Uv,
+                        Some("ico") => SphereMesh::Ico,
+                        _ => return Err(String::from("--sphere espera uv o ico")),
+                    };
+                }
+                "--tonemap" => {
+                    config.render.tone_mapping = match args.next().as_deref() {
+                        Some("reinhard") => ToneMapping::Reinhard,
+                        Some("aces") => ToneMapping::Aces,
+                        _ => return Err(String::from("--tonemap espera reinhard o aces")),
+                    };
+                }
+                "--exposure" => config.render.exposure = parse_value(&arg, args.next())?,
+                "--msaa" => config.antialiasing.msaa = parse_value(&arg, args.next())?,
+                "--ssaa" => config.antialiasing.ssaa = parse_value(&arg, args.next())?,
+                "--orbit-thickness" => config.render.orbit_thickness = parse_value(&arg, args.next())?,
+                "--no-bloom" => config.render.post_processing = false,
+                "--no-milky-way" => config.milky_way = false,
+                "--bloom-threshold" => config.bloom_settings.threshold = parse_value(&arg, args.next())?,
+                "--bloom-intensity" => config.bloom_settings.intensity = parse_value(&arg, args.next())?,
+                _ => return Err(format!("Argumento desconocido: {}", arg)),
+            }
+        }
+
+        if config.width == 0 || config.height == 0 {
+            return Err(String::from("La resolución debe ser mayor que cero"));
+        }
+        if config.frames == 0 {
+            return Err(String::from("--frames debe ser mayor que cero"));
+        }
+        if config.render.exposure.is_nan() || config.render.exposure <= 0.0 {
+            return Err(String::from("La exposición debe ser mayor que cero"));
+        }
+        if ![1, 2, 4, 8].contains(&config.antialiasing.msaa) {
+            return Err(String::from("--msaa espera 1, 2, 4 u 8 muestras"));
+        }
+        if !(1..=4).contains(&config.antialiasing.ssaa) {
+            return Err(String::from("--ssaa espera un factor entre 1 y 4"));
+        }
+        if config.bloom_settings.threshold.is_nan() || config.bloom_settings.threshold < 0.0 {
+            return Err(String::from("--bloom-threshold debe ser un número mayor o igual a cero"));
+        }
+        if config.bloom_settings.intensity.is_nan() || config.bloom_settings.intensity < 0.0 {
+            return Err(String::from("--bloom-intensity debe ser un número mayor o igual a cero"));
+        }
+        if config.render.orbit_thickness.is_nan() || config.render.orbit_thickness <= 0.0 {
+            return Err(String::from("El grosor de las órbitas debe ser mayor que cero"));
+        }
+
+        // Pedir un frame concreto implica simular al menos hasta ese frame
+        if let Some(frame) = config.frame {
+            config.frames = config.frames.max(frame + 1);
+        }
+
+        Ok(config)
+    }
+
+    // Ruta de salida para un frame: "{}" se reemplaza por el número de frame.
+    // Si se exportan varios frames sin "{}", el número se agrega antes de la extensión.
+    pub fn output_path(&self, frame: usize) -> String {
+        let number = format!("{:04}", frame);
+        if self.output.contains("{}") {
+            return self.output.replace("{}", &number);
+        }
+        if self.frame.is_some() || self.frames <= 1 {
+            return self.output.clone();
+        }
+        match self.output.rfind('.') {
+            Some(dot) => format!("{}_{}{}", &self.output[..dot], number, &self.output[dot..]),
+            None => format!("{}_{}", self.output, number),
+        }
+    }
+
+    pub fn print_usage() {
+        println!("Uso: space_travel [opciones]");
+        println!("  --help, -h         Mostrar esta ayuda y salir");
+        println!("  --headless         Renderizar sin ventana y guardar frames en disco");
+        println!("  --bench            Medir tiempo por frame (y memoria con --features alloc-counter), sin ventana ni archivos");
+        println!("  --width N          Ancho del framebuffer (default 1200)");
+        println!("  --height N         Alto del framebuffer (default 800)");
+        println!("  --frames N         Cantidad de frames a simular en modo headless/bench (default 1)");
+        println!("  --frame N          Guardar solo el frame N (empezando en 0)");
+        println!("  --output RUTA      Archivo de salida .png o .ppm (default frame.png)");
+        println!("  --texture-filter F Filtrado de texturas: nearest o bilinear (default bilinear)");
+        println!("  --sphere S         Teselado de las esferas: uv o ico (default ico)");
+        println!("  --no-shadows       Desactivar sombras entre cuerpos (eclipses)");
+        println!("  --tonemap T        Tone mapping HDR: reinhard o aces (default aces)");
+        println!("  --exposure X       Exposición antes del tone mapping (default 1.0)");
+        println!("  --msaa N           Muestras MSAA por píxel: 1, 2, 4 u 8 (default 1)");
+        println!("  --ssaa N           Renderizar a N veces la resolución y reducir, de 1 a 4 (default 1)");
+        println!("  --orbit-thickness X Grosor de las líneas de las órbitas en píxeles (default 1.0)");
+        println!("  --no-bloom         Empezar sin bloom (en la ventana se vuelve a activar con B)");
+        println!("  --bloom-threshold X Luminancia mínima que produce bloom (default 0.6)");
+        println!("  --bloom-intensity X Intensidad del bloom (default 1.0)");
+        println!("  --no-milky-way     Fondo de estrellas sin la banda de la Vía Láctea");
+    }
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("Falta el valor para {}", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Valor inválido para {}: {}", flag, value))
+}
@@ -0,0 +1,20 @@
+use nalgebra_glm::Vec3;
+
+use crate::noise::{fbm3, smoothstep};
+
+// Continentes procedurales de la Tierra cuando no hay texturas. La misma máscara decide
+// el color de día, dónde se encienden las ciudades de noche y dónde brilla el agua,
+// así los tres coinciden sobre la superficie.
+#[derive(Clone, Copy, Debug)]
+pub struct Continents {
+    pub land: f32,  // fracción aproximada de la superficie con tierra firme
+}
+
+impl Continents {
+    // 0 en el océano y 1 en tierra, con una costa suave. `p` es la dirección desde el
+    // centro del planeta en espacio de objeto, así los continentes giran con él.
+    pub fn mask(&self, p: Vec3) -> f32 {
+        let sea_level = 0.62 - 0.25 * self.land;
+        smoothstep(sea_level, sea_level + 0.04, fbm3(p * 1.7 + Vec3::new(3.1, 7.7, 1.3), 4))
+    }
+}
@@ -0,0 +1,130 @@
+use nalgebra_glm::Vec3;
+
+use crate::color::LinearColor;
+use crate::continents::Continents;
+use crate::fragment::Fragment;
+use crate::noise::{fbm3, noise3, smoothstep};
+use crate::shadows::sun_visibility;
+use crate::texture::Texture;
+use crate::Uniforms;
+
+// Luz propia de una superficie que solo se ve de noche (ciudades). Se enciende al
+// cruzar el terminador y se apaga en el lado de día, con una franja de transición.
+pub struct NightLights {
+    pub map: EmissionMap,
+    pub intensity: f32,
+    pub twilight: f32,  // medio ancho de la franja del terminador, en unidades de n·l
+}
+
+// De dónde sale el color de las luces
+pub enum EmissionMap {
+    Texture(Texture),    // mapa equirectangular de luces nocturnas
+    Cities(CityLights),  // ciudades procedurales sobre continentes de ruido
+}
+
+impl NightLights {
+    pub fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let to_light = (uniforms.sun_position - fragment.world_position).normalize();
+        let n_dot_l = fragment.normal.dot(&to_light);
+
+        // 1 de día, 0 de noche; en el crepúsculo la luz se va apagando
+        let mut day = smoothstep(-self.twilight, self.twilight, n_dot_l);
+
+        // Durante un eclipse el lado de día también queda a oscuras: solo cuenta la
+        // fracción visible del sol, igual que en la iluminación de la superficie
+        if uniforms.shadows && day > 0.0 {
+            day *= sun_visibility(
+                &fragment.world_position,
+                &uniforms.sun_position,
+                uniforms.sun_radius,
+                uniforms.occluders,
+            );
+        }
+
+        let night = 1.0 - day;
+        if night <= 0.0 {
+            return LinearColor::black();
+        }
+
+        let lights = match &self.map {
+            EmissionMap::Texture(texture) => texture.sample(fragment.tex_coords),
+            EmissionMap::Cities(cities) => cities.sample(fragment.object_position.normalize()),
+        };
+        lights * (self.intensity * night)
+    }
+}
+
+// Ciudades procedurales en espacio de objeto: giran con el planeta. Solo tienen sentido
+// sobre el albedo procedural (Albedo::Continents) con la misma máscara de continentes.
+pub struct CityLights {
+    pub color: LinearColor,  // tono de las luces (sodio: cálido)
+    pub continents: Continents,
+}
+
+impl CityLights {
+    fn sample(&self, p: Vec3) -> LinearColor {
+        let land = self.continents.mask(p);
+        if land <= 0.0 {
+            return LinearColor::black();
+        }
+
+        // Menos población cerca de los polos
+        let polar = 1.0 - p.y.abs().powi(3);
+
+        // Regiones pobladas y, dentro de ellas, ciudades grandes y pueblos dispersos
+        let regions = smoothstep(0.35, 0.6, fbm3(p * 6.0, 3));
+        let cities = smoothstep(0.6, 0.8, noise3(p * 140.0))
+            + 0.3 * smoothstep(0.5, 0.75, noise3(p * 45.0 + Vec3::new(9.0, 2.0, 5.0)));
+
+        self.color * (land * polar * regions * cities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shadows::Occluder;
+    use nalgebra_glm::{Mat3, Mat4};
+
+    fn uniforms(occluders: &[Occluder]) -> Uniforms<'_> {
+        Uniforms {
+            model_matrix: Mat4::identity(),
+            normal_matrix: Mat3::identity(),
+            view_matrix: Mat4::identity(),
+            projection_matrix: Mat4::identity(),
+            viewport_matrix: Mat4::identity(),
+            camera_position: Vec3::new(0.0, 0.0, 10.0),
+            sun_position: Vec3::new(0.0, 0.0, 1000.0),
+            sun_radius: 100.0,
+            occluders,
+            clouds: None,
+            shadows: true,
+            time: 0.0,
+        }
+    }
+
+    // Luces blancas en toda la superficie; el fragment mira directo al sol
+    fn emission(uniforms: &Uniforms) -> f32 {
+        let lights = NightLights {
+            map: EmissionMap::Texture(Texture::from_pixels(1, 1, vec![LinearColor::new(1.0, 1.0, 1.0)])),
+            intensity: 1.0,
+            twilight: 0.15,
+        };
+        lights.emission(&Fragment::new(0.0, 0.0, 0.0), uniforms).g
+    }
+
+    #[test]
+    fn lights_stay_off_in_daylight() {
+        assert_eq!(emission(&uniforms(&[])), 0.0);
+    }
+
+    #[test]
+    fn lights_turn_on_during_a_total_eclipse() {
+        let moon = [Occluder { center: Vec3::new(0.0, 0.0, 100.0), radius: 20.0 }];
+        assert!((emission(&uniforms(&moon)) - 1.0).abs() < 1e-5);
+
+        // Sin sombras no hay eclipse
+        let no_shadows = Uniforms { shadows: false, ..uniforms(&moon) };
+        assert_eq!(emission(&no_shadows), 0.0);
+    }
+}
@@ -1,18 +1,39 @@
-use nalgebra_glm::Vec2;
-use crate::color::Color;
+use nalgebra_glm::{Vec2, Vec3, Vec4};
 
 pub struct Fragment {
     pub position: Vec2,
-    pub color: Color,
-    pub depth: f32,
+    pub depth: f32,             // profundidad en el centro del píxel
+    pub depth_gradient: Vec2,   // variación de la profundidad por píxel en x e y (para cada muestra MSAA)
+    pub coverage: u32,          // máscara de muestras del píxel cubiertas que pasaron el z-test
+    pub tex_coords: Vec2,
+    pub object_position: Vec3,  // posición en espacio de objeto (rota con el planeta)
+    pub world_position: Vec3,
+    pub normal: Vec3,           // normal en espacio mundial
+    pub tangent: Vec4,          // tangente en espacio mundial (+u), w = orientación de la bitangente
 }
 
 impl Fragment {
-    pub fn new(x: f32, y: f32, color: Color, depth: f32) -> Self {
+    pub fn new(x: f32, y: f32, depth: f32) -> Self {
         Fragment {
             position: Vec2::new(x, y),
-            color,
             depth,
+            depth_gradient: Vec2::new(0.0, 0.0),
+            coverage: 1,
+            tex_coords: Vec2::new(0.0, 0.0),
+            object_position: Vec3::new(0.0, 0.0, 0.0),
+            world_position: Vec3::new(0.0, 0.0, 0.0),
+            normal: Vec3::new(0.0, 0.0, 1.0),
+            tangent: Vec4::new(1.0, 0.0, 0.0, 1.0),
         }
     }
-}
\ No newline at end of file
+}
+
+// Destino de los fragments generados por triangle(). El rasterizador
+// consulta test_depth en cada muestra antes de interpolar atributos y sombrear
+// (early-z), así los fragments ocultos nunca llegan a los shaders.
+pub trait FragmentSink {
+    // Posición de cada muestra dentro del píxel; sin MSAA, solo el centro
+    fn sample_offsets(&self) -> &[Vec2];
+    fn test_depth(&self, x: usize, y: usize, sample: usize, depth: f32) -> bool;
+    fn write(&mut self, fragment: Fragment);
+}
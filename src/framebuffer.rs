@@ -1,50 +1,202 @@
 // framebuffer.rs
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use nalgebra_glm::Vec2;
+
+use crate::color::LinearColor;
+use crate::tonemap::ToneMapping;
+
+// Antialiasing elegido al arrancar; los dos modos se pueden combinar
+#[derive(Clone, Copy, Debug)]
+pub struct Antialiasing {
+    pub msaa: usize,  // muestras de cobertura y profundidad por píxel (1, 2, 4 u 8); se sombrea una vez por píxel
+    pub ssaa: usize,  // factor por eje: se renderiza a ssaa veces la resolución de salida y se reduce al final
+}
+
+impl Default for Antialiasing {
+    fn default() -> Self {
+        Antialiasing { msaa: 1, ssaa: 1 }
+    }
+}
+
 pub struct Framebuffer {
-    pub width: usize,
+    pub width: usize,   // resolución de render (con SSAA, mayor que la de salida)
     pub height: usize,
-    pub buffer: Vec<u32>,
-    pub zbuffer: Vec<f32>,
-    background_color: u32,
-    current_color: u32,
+    pub output_width: usize,
+    pub output_height: usize,
+    pub samples: usize,                 // muestras MSAA por píxel
+    pub sample_offsets: Vec<Vec2>,      // posición de cada muestra dentro del píxel (0..1)
+    pub ssaa: usize,
+    pub buffer: Vec<u32>,               // resultado final en sRGB (0x00RRGGBB) para minifb y archivos
+    pub color_buffer: Vec<LinearColor>, // color HDR lineal de cada muestra, donde se renderiza
+    pub zbuffer: Vec<f32>,              // profundidad de cada muestra
+    pub image: Vec<LinearColor>,        // un color HDR por píxel (muestras ya resueltas) para el post-procesado
+    background_color: LinearColor,
 }
 
 impl Framebuffer {
-    pub fn new(width: usize, height: usize) -> Self {
+    // `width` y `height` son la resolución de salida (ventana o archivo)
+    pub fn new(width: usize, height: usize, antialiasing: Antialiasing) -> Self {
+        let sample_offsets = sample_pattern(antialiasing.msaa);
+        let samples = sample_offsets.len();
+        let ssaa = antialiasing.ssaa.max(1);
+        let (render_width, render_height) = (width * ssaa, height * ssaa);
+        let sample_count = render_width * render_height * samples;
+
         Framebuffer {
-            width,
-            height,
+            width: render_width,
+            height: render_height,
+            output_width: width,
+            output_height: height,
+            samples,
+            sample_offsets,
+            ssaa,
             buffer: vec![0; width * height],
-            zbuffer: vec![f32::INFINITY; width * height],
-            background_color: 0x000000,
-            current_color: 0xFFFFFF,
+            color_buffer: vec![LinearColor::black(); sample_count],
+            zbuffer: vec![f32::INFINITY; sample_count],
+            image: vec![LinearColor::black(); render_width * render_height],
+            background_color: LinearColor::black(),
         }
     }
 
     pub fn clear(&mut self) {
-        for pixel in self.buffer.iter_mut() {
-            *pixel = self.background_color;
+        for sample in self.color_buffer.iter_mut() {
+            *sample = self.background_color;
         }
         for depth in self.zbuffer.iter_mut() {
             *depth = f32::INFINITY;
         }
     }
 
-    pub fn point(&mut self, x: usize, y: usize, depth: f32) {
+    // Sumar luz a todas las muestras de un píxel (fondo de estrellas)
+    pub fn add(&mut self, x: usize, y: usize, color: LinearColor) {
+        let first = (y * self.width + x) * self.samples;
+        for sample in &mut self.color_buffer[first..first + self.samples] {
+            *sample = *sample + color;
+        }
+    }
+
+    // Mezclar un color según su alfa en las muestras que pasan el z-test, sin escribir
+    // profundidad (líneas de las órbitas, que no deben tapar nada de lo que se dibuje después)
+    pub fn blend_point(&mut self, x: usize, y: usize, depth: f32, color: LinearColor) {
         if x < self.width && y < self.height {
-            let index = y * self.width + x;
-            if self.zbuffer[index] > depth {
-                self.buffer[index] = self.current_color;
-                self.zbuffer[index] = depth;
+            let first = (y * self.width + x) * self.samples;
+            for index in first..first + self.samples {
+                if self.zbuffer[index] > depth {
+                    let dst = self.color_buffer[index];
+                    self.color_buffer[index] = dst.lerp(&color, color.a).with_alpha(dst.a);
+                }
             }
         }
     }
 
+    // Resolver MSAA: promedio de las muestras de cada píxel en `image`.
+    // Con una sola muestra por píxel basta con intercambiar los buffers.
+    pub fn resolve_samples(&mut self) {
+        if self.samples == 1 {
+            std::mem::swap(&mut self.image, &mut self.color_buffer);
+            return;
+        }
+
+        let weight = 1.0 / self.samples as f32;
+        for (pixel, samples) in self.image.iter_mut().zip(self.color_buffer.chunks_exact(self.samples)) {
+            let sum = samples.iter().fold(LinearColor::black(), |sum, &sample| sum + sample);
+            *pixel = sum * weight;
+        }
+    }
+
     pub fn set_background_color(&mut self, color: u32) {
-        self.background_color = color;
+        self.background_color = LinearColor::from_hex(color);
+    }
+
+    // Tone mapping + codificación sRGB: convierte `image` en el buffer u32 final.
+    // Con SSAA cada píxel de salida promedia su bloque de ssaa x ssaa píxeles ya
+    // tonemapeados, así los bordes muy brillantes (el sol) también quedan suaves.
+    pub fn resolve(&mut self, tone_mapping: ToneMapping, exposure: f32) {
+        if self.ssaa == 1 {
+            for (output, color) in self.buffer.iter_mut().zip(&self.image) {
+                *output = tone_mapping.apply(*color * exposure).to_srgb_hex();
+            }
+            return;
+        }
+
+        let factor = self.ssaa;
+        let weight = 1.0 / (factor * factor) as f32;
+        for y in 0..self.output_height {
+            for x in 0..self.output_width {
+                let mut sum = LinearColor::black();
+                for sy in y * factor..(y + 1) * factor {
+                    let row = sy * self.width;
+                    for sx in x * factor..(x + 1) * factor {
+                        sum = sum + tone_mapping.apply(self.image[row + sx] * exposure);
+                    }
+                }
+                self.buffer[y * self.output_width + x] = (sum * weight).to_srgb_hex();
+            }
+        }
+    }
+
+    // Guardar el buffer final (después de resolve) en disco; el formato se elige por la extensión
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("ppm") => self.save_ppm(path),
+            Some("png") => self.save_png(path),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Formato de imagen no soportado: {}", path),
+            )),
+        }
+    }
+
+    pub fn save_ppm(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "P6\n{} {}\n255\n", self.output_width, self.output_height)?;
+        writer.write_all(&self.to_rgb_bytes())?;
+        writer.flush()
     }
 
-    pub fn set_current_color(&mut self, color: u32) {
-        self.current_color = color;
+    pub fn save_png(&self, path: &str) -> io::Result<()> {
+        image::save_buffer(
+            path,
+            &self.to_rgb_bytes(),
+            self.output_width as u32,
+            self.output_height as u32,
+            image::ColorType::Rgb8,
+        )
+        .map_err(io::Error::other)
     }
-}
\ No newline at end of file
+
+    // Convertir el buffer 0x00RRGGBB a bytes RGB consecutivos
+    fn to_rgb_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.buffer.len() * 3);
+        for &pixel in &self.buffer {
+            bytes.push(((pixel >> 16) & 0xFF) as u8);
+            bytes.push(((pixel >> 8) & 0xFF) as u8);
+            bytes.push((pixel & 0xFF) as u8);
+        }
+        bytes
+    }
+}
+
+// Posiciones estándar de las muestras (las de Direct3D), en fracciones del píxel.
+// Con una sola muestra se usa el centro, como sin MSAA.
+fn sample_pattern(samples: usize) -> Vec<Vec2> {
+    let pattern: &[(i32, i32)] = match samples {
+        2 => &[(4, 4), (-4, -4)],
+        4 => &[(-2, -6), (6, -2), (-6, 2), (2, 6)],
+        8 => &[(1, -3), (-1, 3), (5, 1), (-3, -5), (-5, 5), (-7, -1), (3, 7), (7, -7)],
+        _ => &[(0, 0)],
+    };
+    pattern
+        .iter()
+        .map(|&(x, y)| Vec2::new(0.5 + x as f32 / 16.0, 0.5 + y as f32 / 16.0))
+        .collect()
+}
@@ -1,99 +1,109 @@
-use crate::color::Color; 
+use nalgebra_glm::Vec3;
+use crate::color::LinearColor;
 use crate::fragment::Fragment; 
+use crate::material::Material;
+use crate::noise::{fbm3, noise3};
+use crate::relief::NormalMap;
+use crate::shaders::{Albedo, FragmentShader};
+use crate::Uniforms;
 
-// Función de ruido simple (Perlin-like simplificado) 
-fn noise(x: f32, y: f32) -> f32 { 
-    let xi = x.floor() as i32; 
-    let yi = y.floor() as i32; 
-    let hash = ((xi.wrapping_mul(374761393) + yi.wrapping_mul(668265263)) ^ (xi.wrapping_mul(668265263))) as f32; 
-    (hash.sin() * 43758.5453).fract() 
-} 
-
-fn fbm(x: f32, y: f32, octaves: i32) -> f32 { 
-    let mut value = 0.0;
-    let mut amplitude = 1.0; 
-    let mut frequency = 1.0; 
-    for _ in 0..octaves { 
-        value += amplitude * noise(x * frequency, y * frequency); 
-        frequency *= 2.0;
-        amplitude *= 0.5;
-    } 
-    value 
-} 
+// Dirección desde el centro del planeta en espacio de objeto: los patrones quedan
+// pegados a la superficie y giran con Planet::rotation
+fn surface_direction(fragment: &Fragment) -> Vec3 {
+    fragment.object_position.normalize()
+}
 
 // Shader para el sol (emisivo, sin iluminación) 
-pub fn sun_shader(fragment: &Fragment, base_color: Color, time: f32) -> Color {
-    let x = fragment.position.x;
-    let y = fragment.position.y;
-
-    // ⬇️ Centro aproximado de la ventana (tu ventana es 1200x800)
-    let cx = 600.0;
-    let cy = 400.0;
+// Todo depende de la normal y la dirección de vista, así que el oscurecimiento del
+// borde funciona desde cualquier punto de vista y con cualquier resolución.
+pub struct SunShader {
+    pub material: Material,  // solo se usa el color emisivo
+}
 
-    // Distancia del fragmento al centro del sol en pantalla
-    let dx = x - cx;
-    let dy = y - cy;
-    let dist = (dx * dx + dy * dy).sqrt();
+impl FragmentShader for SunShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let base_color = self.material.emissive;
+        let time = uniforms.time;
 
-    // Radio aproximado del disco del sol en píxeles (ajusta si lo ves muy fuerte/suave)
-    let max_radius = 120.0;
-    let t = (dist / max_radius).min(1.0);
+        // Coseno del ángulo entre la normal y la dirección hacia la cámara:
+        // 1 en el centro del disco visible, 0 en el borde
+        let to_camera = (uniforms.camera_position - fragment.world_position).normalize();
+        let mu = fragment.normal.dot(&to_camera).clamp(0.0, 1.0);
 
-    // Gradiente: centro brillante, borde más oscuro (1.0 → 0.5)
-    let base_intensity = 1.0 - 0.5 * t;
+        // Limb darkening: centro brillante, borde más oscuro (1.0 → 0.5)
+        let base_intensity = 0.5 + 0.5 * mu.sqrt();
 
-    // Ruido animado para la actividad solar
-    let activity = fbm(x * 0.03 + time * 2.0, y * 0.03 + time * 1.5, 2);
-    let flicker = 1.0 + activity * 0.3;
+        // Granulación animada sobre la superficie (espacio de objeto)
+        let p = surface_direction(fragment);
+        let drift = Vec3::new(time * 0.4, time * 0.3, time * 0.2);
+        let granulation = fbm3(p * 8.0 + drift, 3);
 
-    let intensity = base_intensity * flicker;
+        // Parpadeo global suave de la actividad solar
+        let flicker = 1.0 + (granulation - 0.5) * 0.6 + (time * 3.0).sin() * 0.03;
 
-    Color::new(
-        ((base_color.r as f32 * intensity).min(255.0)) as u8,
-        ((base_color.g as f32 * intensity).min(255.0)) as u8,
-        ((base_color.b as f32 * intensity).min(255.0)) as u8,
-    )
+        // En HDR el brillo puede pasar de 1.0 sin recortarse; el tone mapping lo comprime
+        base_color * (base_intensity * flicker)
+    }
 }
 
 // Shader para planeta gaseoso (como Júpiter)         
-pub fn gaseous_shader(fragment: &Fragment, base_color: Color, time: f32) -> Color { 
-    let x = fragment.position.x; 
-    let y = fragment.position.y; 
-    // Crear bandas horizontales con movimiento 
-    let bands = (y * 0.03 + time * 0.5).sin() * 0.5 + 0.5; 
-    // Agregar turbulencia 
-    let turbulence = fbm(x * 0.02 + time, y * 0.02, 3); 
-    // Combinar efectos 
-    let pattern = (bands + turbulence * 0.3).clamp(0.0, 1.0); 
-    // Variación de color 
-    let color_variation = 0.7 + pattern * 0.6; 
-
-    Color::new(
-        (base_color.r as f32 * color_variation) as u8,
-        (base_color.g as f32 * color_variation) as u8,
-        (base_color.b as f32 * color_variation) as u8,
-    ) 
-} 
+pub struct GaseousShader {
+    pub albedo: Albedo,
+    pub material: Material,
+}
+
+impl FragmentShader for GaseousShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let base_color = self.albedo.sample(fragment);
+        let time = uniforms.time;
+
+        let p = surface_direction(fragment);
+        // Latitud en [-PI/2, PI/2]; el eje Y del objeto es el eje de rotación
+        let latitude = p.y.clamp(-1.0, 1.0).asin();
+        // Crear bandas horizontales con movimiento 
+        let bands = (latitude * 12.0 + time * 0.5).sin() * 0.5 + 0.5; 
+        // Agregar turbulencia: el punto de muestreo gira lentamente alrededor del eje
+        let (sin_t, cos_t) = (time * 0.1).sin_cos();
+        let swirl = Vec3::new(p.x * cos_t - p.z * sin_t, p.y * 3.0, p.x * sin_t + p.z * cos_t);
+        let turbulence = fbm3(swirl * 3.0, 3); 
+        // Combinar efectos 
+        let pattern = (bands + (turbulence - 0.5) * 0.6).clamp(0.0, 1.0); 
+        // Variación de color 
+        let color_variation = 0.7 + pattern * 0.6; 
+
+        let banded_color = base_color * color_variation;
+
+        self.material.shade(banded_color, fragment, uniforms)
+    }
+}
 
 // Shader para planeta rocoso (como Marte) 
-pub fn rocky_shader(fragment: &Fragment, base_color: Color) -> Color { 
-    let x = fragment.position.x; 
-    let y = fragment.position.y; 
-    // Crear textura rocosa con múltiples octavas de ruido 
-    let rock_noise = fbm(x * 0.05, y * 0.05, 4); 
-    // Crear cráteres (manchas oscuras) 
-    let crater_noise = noise(x * 0.03, y * 0.03); 
-    let craters = if crater_noise > 0.85 { 
-        0.5 // Área de cráter más oscura 
-    } else {
-        1.0
-    }; 
-    // Combinar efectos 
-    let pattern = (rock_noise * 0.4 + 0.6) * craters; 
-    
-    Color::new(
-        (base_color.r as f32 * pattern) as u8,
-        (base_color.g as f32 * pattern) as u8,
-        (base_color.b as f32 * pattern) as u8,
-    ) 
-}
\ No newline at end of file
+pub struct RockyShader {
+    pub albedo: Albedo,
+    pub relief: Option<NormalMap>,  // cráteres y montañas que responden a la dirección del sol
+    pub material: Material,
+}
+
+impl FragmentShader for RockyShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let base_color = self.albedo.sample(fragment);
+
+        let p = surface_direction(fragment);
+        // Crear textura rocosa con múltiples octavas de ruido 
+        let rock_noise = fbm3(p * 4.0, 4); 
+        let rock_color = base_color * (rock_noise * 0.8 + 0.6);
+
+        match &self.relief {
+            Some(relief) => {
+                let normal = relief.perturb(fragment);
+                self.material.shade_with_normal(rock_color, normal, fragment, uniforms)
+            }
+            None => {
+                // Sin relieve, los cráteres se sugieren oscureciendo el color
+                let crater_noise = noise3(p * 5.0 + Vec3::new(17.0, 5.0, 11.0));
+                let craters = if crater_noise > 0.75 { 0.5 } else { 1.0 };
+                self.material.shade(rock_color * craters, fragment, uniforms)
+            }
+        }
+    }
+}
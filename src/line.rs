@@ -1,42 +1,125 @@
-use crate::fragment::Fragment;
+use nalgebra_glm::{dot, Vec2};
+
+use crate::color::LinearColor;
+use crate::framebuffer::Framebuffer;
 use crate::vertex::Vertex;
-use crate::color::Color;
 
-pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
+// Línea con antialiasing por cobertura: cada píxel cercano recibe la fracción del trazo
+// (de `thickness` píxeles de ancho) que lo cubre. La profundidad y el color se interpolan
+// con el parámetro t del segmento, así que funciona igual en cualquier dirección.
+// Los píxeles se mezclan con z-test pero sin escribir profundidad.
+pub fn line(
+    start: &Vertex,
+    end: &Vertex,
+    start_color: LinearColor,
+    end_color: LinearColor,
+    thickness: f32,
+    framebuffer: &mut Framebuffer,
+) {
+    let (a, b) = (start.transformed_position, end.transformed_position);
+    let origin = Vec2::new(a.x, a.y);
+    let delta = Vec2::new(b.x - a.x, b.y - a.y);
+    let length_squared = dot(&delta, &delta);
+    if length_squared < 1e-8 {
+        return;
+    }
 
-    let start = a.transformed_position;
-    let end = b.transformed_position;
+    // Trazos de menos de un píxel se dibujan de un píxel con menos opacidad
+    let half_width = thickness.max(1.0) * 0.5;
+    let opacity = thickness.clamp(0.0, 1.0);
+    let reach = half_width + 1.0;
 
-    let mut x0 = start.x as i32;
-    let mut y0 = start.y as i32;
-    let x1 = end.x as i32;
-    let y1 = end.y as i32;
+    // Recorrer el eje mayor; en el eje menor solo los píxeles al alcance del trazo
+    let steep = delta.y.abs() > delta.x.abs();
+    let (a_major, a_minor, b_major, b_minor) = if steep { (a.y, a.x, b.y, b.x) } else { (a.x, a.y, b.x, b.y) };
+    let (major_size, minor_size) = if steep {
+        (framebuffer.height as i32, framebuffer.width as i32)
+    } else {
+        (framebuffer.width as i32, framebuffer.height as i32)
+    };
+    let slope = (b_minor - a_minor) / (b_major - a_major);
+    let minor_reach = reach * (1.0 + slope * slope).sqrt();
 
-    let dx = (x1 - x0).abs();
-    let dy = (y1 - y0).abs();
+    let major_min = ((a_major.min(b_major) - reach).floor() as i32).max(0);
+    let major_max = ((a_major.max(b_major) + reach).ceil() as i32).min(major_size - 1);
 
-    let sx = if x0 < x1 { 1 } else { -1 };
-    let sy = if y0 < y1 { 1 } else { -1 };
+    for major in major_min..=major_max {
+        let minor_center = a_minor + slope * (major as f32 + 0.5 - a_major);
+        let minor_min = ((minor_center - minor_reach).floor() as i32).max(0);
+        let minor_max = ((minor_center + minor_reach).ceil() as i32).min(minor_size - 1);
 
-    let mut err = if dx > dy { dx / 2 } else { -dy / 2 };
+        for minor in minor_min..=minor_max {
+            let (x, y) = if steep { (minor, major) } else { (major, minor) };
+            let pixel = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
 
-    loop {
-        let z = start.z + (end.z - start.z) * (x0 - start.x as i32) as f32 / (end.x - start.x) as f32;
-        fragments.push(Fragment::new(x0 as f32, y0 as f32, Color::new(255, 255, 255), z));
+            // Extremos planos y semiabiertos: los segmentos consecutivos de una
+            // polilínea no pintan dos veces el vértice que comparten
+            let t = dot(&(pixel - origin), &delta) / length_squared;
+            if !(0.0..1.0).contains(&t) {
+                continue;
+            }
 
-        if x0 == x1 && y0 == y1 { break; }
+            let distance = (pixel - (origin + delta * t)).magnitude();
+            let coverage = (half_width + 0.5 - distance).clamp(0.0, 1.0);
+            if coverage <= 0.0 {
+                continue;
+            }
 
-        let e2 = err;
-        if e2 > -dx {
-            err -= dy;
-            x0 += sx;
-        }
-        if e2 < dy {
-            err += dx;
-            y0 += sy;
+            let depth = a.z + (b.z - a.z) * t;
+            let color = start_color.lerp(&end_color, t);
+            framebuffer.blend_point(x as usize, y as usize, depth, color.with_alpha(color.a * coverage * opacity));
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::Antialiasing;
+    use nalgebra_glm::Vec3;
+
+    fn screen_vertex(x: f32, y: f32, depth: f32) -> Vertex {
+        let mut vertex = Vertex::new(Vec3::zeros(), Vec3::new(0.0, 0.0, 1.0), Vec2::zeros());
+        vertex.transformed_position = Vec3::new(x, y, depth);
+        vertex
+    }
+
+    // Píxeles pintados: la línea es blanca y opaca sobre un fondo negro
+    fn painted(framebuffer: &Framebuffer) -> Vec<(usize, usize)> {
+        (0..framebuffer.height)
+            .flat_map(|y| (0..framebuffer.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| framebuffer.color_buffer[y * framebuffer.width + x].g > 0.0)
+            .collect()
+    }
 
-    fragments
-}
\ No newline at end of file
+    fn draw(start: &Vertex, end: &Vertex, framebuffer: &mut Framebuffer) {
+        let white = LinearColor::new(1.0, 1.0, 1.0);
+        line(start, end, white, white, 1.0, framebuffer);
+    }
+
+    #[test]
+    fn vertical_segment_has_finite_depth() {
+        let mut framebuffer = Framebuffer::new(10, 10, Antialiasing::default());
+        let (start, end) = (screen_vertex(5.5, 2.0, 0.2), screen_vertex(5.5, 8.0, 0.8));
+
+        draw(&start, &end, &mut framebuffer);
+        assert_eq!(painted(&framebuffer), (2..8).map(|y| (5, y)).collect::<Vec<_>>());
+
+        // Con la mitad más lejana tapada solo queda la mitad cercana: la profundidad
+        // se interpola sin NaN (un NaN no pasaría ningún z-test)
+        let mut framebuffer = Framebuffer::new(10, 10, Antialiasing::default());
+        framebuffer.zbuffer.iter_mut().for_each(|depth| *depth = 0.5);
+        draw(&start, &end, &mut framebuffer);
+        assert_eq!(painted(&framebuffer), (2..5).map(|y| (5, y)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn zero_length_segment_draws_nothing() {
+        let mut framebuffer = Framebuffer::new(10, 10, Antialiasing::default());
+        let point = screen_vertex(4.5, 4.5, 0.5);
+
+        draw(&point, &point, &mut framebuffer);
+        assert!(painted(&framebuffer).is_empty());
+        assert!(framebuffer.color_buffer.iter().all(|color| color.r.is_finite() && color.g.is_finite()));
+    }
+}
@@ -1,7 +1,8 @@
-use nalgebra_glm::{Vec3, Mat4};
+use nalgebra_glm::{Vec3, Vec4, Mat3, Mat4};
 use minifb::{Key, Window, WindowOptions};
 use std::time::{Duration, Instant};
-use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
 
 mod framebuffer;
 mod triangle;
@@ -13,35 +14,66 @@ mod fragment;
 mod shaders;
 mod planet;
 mod gaseous_shader;
+mod noise;
+mod continents;
 mod camera;
 mod spaceship;
+mod scene;
+mod config;
+mod clipping;
+mod rasterizer;
+#[cfg(feature = "alloc-counter")]
+mod alloc_counter;
+mod texture;
+mod material;
+mod shadows;
+mod tonemap;
+mod postprocess;
+mod atmosphere;
+mod ring;
+mod starfield;
+mod relief;
+mod emission;
+mod clouds;
+mod sphere;
+mod mesh;
 
 use framebuffer::Framebuffer;
 use vertex::Vertex;
-use obj::Obj;
-use triangle::triangle;
+use mesh::Mesh;
+use triangle::CullMode;
+use rasterizer::{rasterize, BlendMode, PipelineState};
 use line::line;
-use shaders::vertex_shader;
-use planet::Planet;
-use color::Color;
-use gaseous_shader::{gaseous_shader, rocky_shader, sun_shader};
+use shaders::{vertex_shader, normal_matrix, project_to_screen, FragmentShader};
+use clipping::{box_outside_frustum, clip_triangle, clip_line, Clip};
 use camera::Camera;
-use spaceship::Spaceship;
-
-pub struct Uniforms {
+use planet::Planet;
+use scene::{Orbit, Scene};
+use config::{Config, RenderSettings};
+use shadows::Occluder;
+use clouds::CloudShadow;
+use tonemap::ToneMapping;
+use postprocess::PostProcessChain;
+
+// Contar cada reserva tiene un costo: el allocator que cuenta solo se instala al compilar
+// con `--features alloc-counter`, para medir la memoria en el modo --bench
+#[cfg(feature = "alloc-counter")]
+#[global_allocator]
+static ALLOCATOR: alloc_counter::CountingAllocator = alloc_counter::CountingAllocator;
+
+pub struct Uniforms<'a> {
     model_matrix: Mat4,
+    normal_matrix: Mat3,  // inversa transpuesta de model_matrix; la calcula render() en cada draw
     view_matrix: Mat4,
     projection_matrix: Mat4,
     viewport_matrix: Mat4,
-}
-
-#[derive(Clone, Copy, PartialEq)]
-pub enum PlanetType {
-    Sun,
-    Gaseous,
-    Rocky,
-    Normal,
-    Spaceship,
+    camera_position: Vec3,
+    sun_position: Vec3,
+    sun_radius: f32,
+    occluders: &'a [Occluder],  // cuerpos que proyectan sombra
+    clouds: Option<CloudShadow<'a>>,  // nubes sobre el objeto que se dibuja
+    shadows: bool,
+    time: f32,
 }
 
 fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
@@ -82,6 +114,14 @@ fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
     transform_matrix * rotation_matrix
 }
 
+// Modelo de un planeta o de una de sus capas: gira `spin` sobre el eje Y del objeto y
+// después inclina ese eje `planet.tilt` alrededor de X. Así el ecuador, las bandas y los
+// anillos quedan en el mismo plano.
+fn planet_model_matrix(planet: &Planet, scale: f32, spin: f32) -> Mat4 {
+    let tilted = create_model_matrix(planet.translation, scale, Vec3::new(planet.tilt, 0.0, 0.0));
+    tilted * create_model_matrix(Vec3::zeros(), 1.0, Vec3::new(0.0, spin, 0.0))
+}
+
 fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
     Mat4::new(
         width / 2.0, 0.0, 0.0, width / 2.0,
@@ -94,173 +134,464 @@ fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
 fn render(
     framebuffer: &mut Framebuffer,
     uniforms: &Uniforms,
-    vertex_array: &[Vertex],
-    color: u32,
-    planet_type: PlanetType,
-    time: f32,
-    sun_world_position: Vec3
+    mesh: &Mesh,
+    shader: &dyn FragmentShader,
+    state: &PipelineState,
 ) {
-    let is_sun = planet_type == PlanetType::Sun;
-
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    for vertex in vertex_array {
-        let mut transformed = vertex_shader(vertex, uniforms);
-        transformed.color = Color::from_hex(color);
-        transformed_vertices.push(transformed);
+    // Objeto entero fuera de la vista: ni siquiera transformar sus vértices
+    let model_view_projection = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix;
+    if box_outside_frustum(&mesh.bounds.min, &mesh.bounds.max, &model_view_projection) {
+        return;
     }
 
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+    // Matriz de normales: una inversión por draw en lugar de una por vértice
+    let uniforms = &Uniforms {
+        normal_matrix: normal_matrix(&uniforms.model_matrix),
+        ..*uniforms
+    };
+
+    // Caché post-transformación: cada vértice único pasa una vez por el vertex shader y,
+    // la primera vez que lo usa un triángulo que no hay que recortar, se proyecta y se
+    // guarda su índice en `screen_vertices`. Los demás triángulos que lo comparten lo reusan.
+    let transformed_vertices: Vec<Vertex> = mesh.vertices
+        .iter()
+        .map(|vertex| vertex_shader(vertex, uniforms))
+        .collect();
+    let mut screen_index = vec![usize::MAX; transformed_vertices.len()];
+
+    // Los triángulos recortados generan vértices nuevos, que se agregan sin compartir
+    let mut screen_vertices = Vec::with_capacity(transformed_vertices.len());
+    let mut triangles = Vec::with_capacity(mesh.triangle_count());
+    let (mut polygon, mut scratch) = (Vec::new(), Vec::new());
+
+    for indices in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [indices[0] as usize, indices[1] as usize, indices[2] as usize];
+        let (va, vb, vc) = (&transformed_vertices[a], &transformed_vertices[b], &transformed_vertices[c]);
+
+        match clip_triangle(va, vb, vc, &mut polygon, &mut scratch) {
+            Clip::Outside => {}
+            Clip::Inside => {
+                let mut projected = |index: usize| {
+                    if screen_index[index] == usize::MAX {
+                        let mut vertex = transformed_vertices[index].clone();
+                        project_to_screen(&mut vertex, &uniforms.viewport_matrix);
+                        screen_index[index] = screen_vertices.len();
+                        screen_vertices.push(vertex);
+                    }
+                    screen_index[index]
+                };
+                triangles.push([projected(a), projected(b), projected(c)]);
+            }
+            Clip::Clipped => {
+                let first = screen_vertices.len();
+                let count = polygon.len();
+                for mut vertex in polygon.drain(..) {
+                    project_to_screen(&mut vertex, &uniforms.viewport_matrix);
+                    screen_vertices.push(vertex);
+                }
+
+                // Triangular el polígono recortado en abanico
+                for j in 1..count - 1 {
+                    triangles.push([first, first + j, first + j + 1]);
+                }
+            }
         }
     }
 
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], &sun_world_position, is_sun));
+    rasterize(
+        framebuffer,
+        &screen_vertices,
+        &triangles,
+        state,
+        |fragment| shader.shade(fragment, uniforms),
+    );
+}
+
+// Draw transparente pendiente; se ordenan por distancia a la cámara antes de dibujarlos
+struct TransparentDraw<'a> {
+    distance: f32,
+    uniforms: Uniforms<'a>,
+    mesh: &'a Mesh,
+    shader: &'a dyn FragmentShader,
+    state: PipelineState,
+}
+
+// Centro del objeto en espacio mundial (el origen del modelo transformado)
+fn object_center(model_matrix: &Mat4) -> Vec3 {
+    let center = model_matrix * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    Vec3::new(center.x, center.y, center.z)
+}
+
+fn render_scene(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    camera: &Camera,
+    settings: &RenderSettings,
+    post_process: &mut PostProcessChain,
+    time: f32,
+) {
+    let sun = &scene.sun;
+    framebuffer.clear();
+
+    // Crear matrices de transformación
+    let view_matrix = camera.get_view_matrix();
+    let projection_matrix = camera.get_projection_matrix(
+        framebuffer.width as f32,
+        framebuffer.height as f32
+    );
+    let viewport_matrix = create_viewport_matrix(
+        framebuffer.width as f32,
+        framebuffer.height as f32
+    );
+
+    // Fondo de estrellas (solo depende de la rotación de la cámara)
+    scene.starfield.draw(framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+
+    let orbit_model_matrix = Mat4::new(
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    );
+
+    // Todos los planetas pueden eclipsar a otros cuerpos
+    let occluders: Vec<Occluder> = scene.planets
+        .iter()
+        .map(|planet| Occluder {
+            center: planet.translation,
+            radius: planet.scale * scene.sphere_radius,
+        })
+        .collect();
+
+    // Uniforms compartidos por todos los objetos del frame; cada objeto solo cambia su model_matrix
+    let orbit_uniforms = Uniforms {
+        model_matrix: orbit_model_matrix,
+        normal_matrix: Mat3::identity(),
+        view_matrix,
+        projection_matrix,
+        viewport_matrix,
+        camera_position: camera.eye,
+        sun_position: sun.translation,
+        sun_radius: sun.scale * scene.sphere_radius,
+        occluders: &occluders,
+        clouds: None,
+        shadows: settings.shadows,
+        time,
+    };
+
+    // Las esferas eligen su nivel de detalle según su tamaño en píxeles de render
+    let lod_height = framebuffer.height as f32;
+
+    // Renderizar el sol
+    let sun_uniforms = Uniforms {
+        model_matrix: create_model_matrix(sun.translation, sun.scale, sun.rotation),
+        ..orbit_uniforms
+    };
+    render(
+        framebuffer,
+        &sun_uniforms,
+        sphere_lod(scene, &sun_uniforms, lod_height),
+        sun.shader.as_ref(),
+        &PipelineState::opaque(CullMode::Back),
+    );
+
+    // Renderizar planetas
+    for planet in &scene.planets {
+        let uniforms = Uniforms {
+            model_matrix: planet_model_matrix(planet, planet.scale, planet.rotation.y),
+            clouds: planet.clouds.as_ref().map(|clouds| CloudShadow {
+                shader: &clouds.shader,
+                center: planet.translation,
+                radius: planet.scale * clouds.scale * scene.sphere_radius,
+                tilt: planet.tilt,
+                rotation: clouds.rotation,
+            }),
+            ..orbit_uniforms
+        };
+        render(
+            framebuffer,
+            &uniforms,
+            sphere_lod(scene, &uniforms, lod_height),
+            planet.shader.as_ref(),
+            &PipelineState::opaque(CullMode::Back),
+        );
     }
 
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
-        if x < framebuffer.width && y < framebuffer.height {
-            let final_color = match planet_type {
-                PlanetType::Sun => sun_shader(&fragment, fragment.color, time),
-                PlanetType::Gaseous => gaseous_shader(&fragment, fragment.color, time),
-                PlanetType::Rocky => rocky_shader(&fragment, fragment.color),
-                PlanetType::Normal => fragment.color,
-                PlanetType::Spaceship => fragment.color,
+    // Renderizar nave espacial (al final para que se vea encima)
+    let spaceship = &scene.spaceship;
+    let spaceship_position = spaceship.get_world_position(camera);
+    let spaceship_rotation = spaceship.get_world_rotation(camera);
+    let spaceship_model_matrix = create_model_matrix(
+        spaceship_position,
+        spaceship.scale,
+        spaceship_rotation
+    );
+    let spaceship_uniforms = Uniforms {
+        model_matrix: spaceship_model_matrix,
+        ..orbit_uniforms
+    };
+    render(
+        framebuffer,
+        &spaceship_uniforms,
+        &scene.spaceship_mesh,
+        &scene.spaceship_shader,
+        &PipelineState::opaque(CullMode::None),  // La malla de la nave es abierta: dibujar ambas caras
+    );
+
+    // Órbitas: después de lo opaco para que los planetas las tapen, antes de las capas
+    // transparentes para que atmósferas y anillos queden por encima. El grosor se da
+    // en píxeles de salida
+    for orbit in &scene.orbits {
+        draw_orbit(framebuffer, orbit, &orbit_uniforms, settings.orbit_thickness * framebuffer.ssaa as f32);
+    }
+
+    // Objetos transparentes: después de todo lo opaco, del más lejano al más cercano,
+    // probando profundidad sin escribirla
+    let mut transparent_draws: Vec<TransparentDraw> = Vec::new();
+    for planet in &scene.planets {
+        // Las nubes van antes que la atmósfera: están a la misma distancia y el orden
+        // es estable, así la capa exterior queda encima
+        if let Some(clouds) = &planet.clouds {
+            let uniforms = Uniforms {
+                model_matrix: planet_model_matrix(planet, planet.scale * clouds.scale, clouds.rotation),
+                ..orbit_uniforms
             };
-            
-            let color = final_color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth);
+            transparent_draws.push(TransparentDraw {
+                distance: (planet.translation - camera.eye).magnitude(),
+                mesh: sphere_lod(scene, &uniforms, lod_height),
+                uniforms,
+                shader: &clouds.shader,
+                state: PipelineState::transparent(BlendMode::Alpha),
+            });
         }
+
+        if let Some(atmosphere) = &planet.atmosphere {
+            let uniforms = Uniforms {
+                model_matrix: planet_model_matrix(planet, planet.scale * atmosphere.scale, planet.rotation.y),
+                ..orbit_uniforms
+            };
+            transparent_draws.push(TransparentDraw {
+                distance: (planet.translation - camera.eye).magnitude(),
+                mesh: sphere_lod(scene, &uniforms, lod_height),
+                uniforms,
+                shader: &atmosphere.shader,
+                state: PipelineState::transparent(BlendMode::Alpha),
+            });
+        }
+
+        if let Some(rings) = &planet.rings {
+            transparent_draws.push(TransparentDraw {
+                distance: (planet.translation - camera.eye).magnitude(),
+                uniforms: Uniforms {
+                    model_matrix: planet_model_matrix(
+                        planet,
+                        planet.scale * scene.sphere_radius,  // radios del anillo en radios del planeta
+                        0.0,
+                    ),
+                    ..orbit_uniforms
+                },
+                mesh: &rings.mesh,
+                shader: &rings.shader,
+                state: PipelineState {
+                    cull_mode: CullMode::None,  // visible desde ambas caras
+                    ..PipelineState::transparent(BlendMode::Alpha)
+                },
+            });
+        }
+    }
+    for layer in &scene.spaceship_exhaust {
+        let model_matrix = layer.model_matrix(&spaceship_model_matrix, scene.sphere_radius);
+        let uniforms = Uniforms { model_matrix, ..orbit_uniforms };
+        transparent_draws.push(TransparentDraw {
+            distance: (object_center(&model_matrix) - camera.eye).magnitude(),
+            mesh: sphere_lod(scene, &uniforms, lod_height),
+            uniforms,
+            shader: &layer.shader,
+            state: PipelineState::transparent(layer.blend_mode),
+        });
+    }
+
+    transparent_draws.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+    for draw in &transparent_draws {
+        render(framebuffer, &draw.uniforms, draw.mesh, draw.shader, &draw.state);
     }
+
+    // Promediar las muestras MSAA en un color por píxel
+    framebuffer.resolve_samples();
+
+    // Post-procesado sobre el buffer HDR (bloom del sol y materiales emisivos)
+    if settings.post_processing {
+        post_process.apply(framebuffer);
+    }
+
+    // Pasar del buffer HDR al buffer u32 que se muestra o se guarda
+    framebuffer.resolve(settings.tone_mapping, settings.exposure);
 }
 
-fn main() {
-    let window_width = 1200;
-    let window_height = 800;
-    let framebuffer_width = 1200;
-    let framebuffer_height = 800;
-    let frame_delay = Duration::from_millis(16);
+// Nivel de detalle de la esfera de un draw según su radio en pantalla. Se usa la esfera
+// envolvente de la malla con la mayor escala del modelo, así también sirve para esferas
+// estiradas (escape de la nave)
+fn sphere_lod<'a>(scene: &'a Scene, uniforms: &Uniforms, height: f32) -> &'a Mesh {
+    let bounds = scene.spheres.bounds();
+    let model = &uniforms.model_matrix;
+    let scale = (0..3)
+        .map(|i| Vec3::new(model[(0, i)], model[(1, i)], model[(2, i)]).magnitude())
+        .fold(0.0, f32::max);
+    let radius = bounds.radius * scale;
+    let center = model * Vec4::new(bounds.center.x, bounds.center.y, bounds.center.z, 1.0);
+
+    // Con la cámara dentro de la esfera, el nivel más fino
+    let distance = (center.xyz() - uniforms.camera_position).magnitude();
+    if distance <= radius {
+        return scene.spheres.select(f32::INFINITY);
+    }
 
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
-    let mut window = Window::new(
-        "Sistema Solar 3D - Rust Graphics",
-        window_width,
-        window_height,
-        WindowOptions::default(),
-    )
-    .unwrap();
+    // Radio angular del borde visto desde la cámara, pasado a píxeles con la proyección
+    let screen_radius = (radius / distance).asin().tan() * uniforms.projection_matrix[(1, 1)] * height * 0.5;
+    scene.spheres.select(screen_radius)
+}
 
-    window.set_position(100, 100);
-    framebuffer.set_background_color(0x000011);
+// Dibujar una órbita como líneas entre vértices consecutivos, recortando cada segmento
+// para no dibujar lo que queda detrás de la cámara
+fn draw_orbit(framebuffer: &mut Framebuffer, orbit: &Orbit, uniforms: &Uniforms, thickness: f32) {
+    // 1) Transformar cada vértice de la órbita a espacio de pantalla
+    let transformed: Vec<Vertex> = orbit.vertices
+        .iter()
+        .map(|v| vertex_shader(v, uniforms))
+        .collect();
+
+    // La mitad lejana se atenúa para que la órbita se lea como un círculo en 3D:
+    // `side` vale 1 en el punto más cercano a la cámara y -1 en el más lejano
+    let to_camera = uniforms.camera_position - orbit.center;
+    let distance = to_camera.magnitude().max(1e-6);
+    let color_at = |vertex: &Vertex| {
+        let side = (vertex.position - orbit.center).dot(&to_camera) / (orbit.radius * distance);
+        let fade = 0.6 + 0.4 * side.clamp(-1.0, 1.0);
+        orbit.color.with_alpha(orbit.color.a * fade)
+    };
 
-    // Cargar modelos
-    let sphere_obj = Obj::load("assets/models/sphere.obj").expect("Failed to load sphere");
-    let vertex_arrays = sphere_obj.get_vertex_array();
-    
-    // Cargar nave espacial
-    let spaceship_obj = Obj::load("assets/models/spaceship.obj").expect("Failed to load spaceship");
-    let spaceship_vertices = spaceship_obj.get_vertex_array();
-    
-    println!("Nave cargada con {} vértices", spaceship_vertices.len());
+    // 2) Cerrar el círculo uniendo el último vértice con el primero
+    let n = transformed.len();
+    for i in 0..n {
+        let a = &transformed[i];
+        let b = &transformed[(i + 1) % n];
+        if let Some((mut start, mut end)) = clip_line(a, b) {
+            // El color se calcula con la posición mundial del vértice recortado
+            let (start_color, end_color) = (color_at(&start), color_at(&end));
+            project_to_screen(&mut start, &uniforms.viewport_matrix);
+            project_to_screen(&mut end, &uniforms.viewport_matrix);
+            line(&start, &end, start_color, end_color, thickness, framebuffer);
+        }
+    }
+}
 
+fn create_camera() -> Camera {
     // Crear cámara - mirando desde atrás hacia el origen
-    let mut camera = Camera::new(
+    Camera::new(
         Vec3::new(0.0, 0.0, 900.0),  // eye - MÁS LEJOS para ver todos los planetas
         Vec3::new(0.0, 0.0, 0.0),    // center - origen
         Vec3::new(0.0, 1.0, 0.0),    // up
-    );
-    
-    // Crear sistema de nave espacial
-    let mut spaceship = Spaceship::new();
+    )
+}
 
-    // Configuración del sistema solar - centrado en el origen
-    let center = Vec3::new(0.0, 0.0, 0.0);
-    
-    // SOL
-    let mut sun = Planet {
-        translation: center,
-        rotation: Vec3::new(0.0, 0.0, 0.0),
-        scale: 80.0,
-        orbit_speed: 0.0,
-        rotation_speed: 0.0,
-        orbit_radius: 0.0,
-        orbit_angle: 0.0,
-        center_x: center.x,
-        center_y: center.y,
-    };
+// Renderizar sin ventana: el tiempo avanza a 60 FPS fijos para que la salida sea reproducible
+fn run_headless(config: &Config) {
+    let mut framebuffer = Framebuffer::new(config.width, config.height, config.antialiasing);
+    framebuffer.set_background_color(0x000011);
 
-    // PLANETAS (5 planetas para conseguir 50 puntos)
-    let mut planets = vec![
-        // Mercurio - Rocoso pequeño
-        Planet::new(180.0, 15.0, 0.04, 0.03, 0.0),
-        // Venus - Rocoso
-        Planet::new(240.0, 25.0, 0.03, 0.025, PI / 4.0),
-        // Tierra - Normal con agua
-        Planet::new(310.0, 28.0, 0.025, 0.02, PI / 2.0),
-        // Marte - Rocoso rojo
-        Planet::new(380.0, 22.0, 0.02, 0.018, 3.0 * PI / 4.0),
-        // Júpiter - Gaseoso grande
-        Planet::new(510.0, 55.0, 0.01, 0.04, PI),
-    ];
-
-    // Configurar profundidad Z para cada planeta
-    for (i, planet) in planets.iter_mut().enumerate() {
-        planet.translation.z = (i as f32 - 2.0) * 50.0;
-    }
+    let mut scene = Scene::new(config);
+    let camera = create_camera();
+    let mut post_process = PostProcessChain::from_config(config);
 
-    const ORBIT_SEGMENTS: usize = 120;
+    for frame in 0..config.frames {
+        let time = frame as f32 / 60.0;
 
-    // Para cada planeta (que no sea el sol, radio 0) creamos un anillo de vértices
-    let mut orbit_vertex_rings: Vec<Vec<Vertex>> = Vec::new();
+        scene.update();
+        render_scene(&mut framebuffer, &scene, &camera, &config.render, &mut post_process, time);
 
-    for planet in &planets {
-        // Ignorar "órbita" del sol (radio 0)
-        if planet.orbit_radius <= 0.0 {
-            continue;
+        if config.frame.is_none_or(|target| target == frame) {
+            let path = config.output_path(frame);
+            if let Some(parent) = Path::new(&path).parent() {
+                if !parent.as_os_str().is_empty() {
+                    fs::create_dir_all(parent)
+                        .unwrap_or_else(|e| panic!("No se pudo crear {}: {}", parent.display(), e));
+                }
+            }
+            framebuffer
+                .save(&path)
+                .unwrap_or_else(|e| panic!("No se pudo guardar {}: {}", path, e));
+            println!("Frame {} guardado en {}", frame, path);
         }
+    }
+}
 
-        let mut ring: Vec<Vertex> = Vec::with_capacity(ORBIT_SEGMENTS);
+// Medir el costo de renderizar: tiempo y memoria reservada por frame
+fn run_bench(config: &Config) {
+    let mut framebuffer = Framebuffer::new(config.width, config.height, config.antialiasing);
+    framebuffer.set_background_color(0x000011);
 
-        for i in 0..ORBIT_SEGMENTS {
-            let angle = 2.0 * PI * (i as f32) / (ORBIT_SEGMENTS as f32);
-            let x = planet.center_x + planet.orbit_radius * angle.cos();
-            let y = planet.center_y + planet.orbit_radius * angle.sin();
-            let z = planet.translation.z; // misma profundidad aproximada que el planeta
+    let mut scene = Scene::new(config);
+    let camera = create_camera();
+    let mut post_process = PostProcessChain::from_config(config);
 
-            let mut v = Vertex::default();
-            v.position = Vec3::new(x, y, z);
-            v.color = Color::from_hex(0xAAAAAA); // color base de la órbita
-            ring.push(v);
-        }
+    let start_memory = allocation_snapshot();
+    let start = Instant::now();
+
+    for frame in 0..config.frames {
+        let time = frame as f32 / 60.0;
+        scene.update();
+        render_scene(&mut framebuffer, &scene, &camera, &config.render, &mut post_process, time);
+    }
 
-        orbit_vertex_rings.push(ring);
+    let elapsed = start.elapsed();
+    let end_memory = allocation_snapshot();
+    let frames = config.frames as f64;
+
+    println!("=== BENCHMARK ({} frames, {}x{}) ===", config.frames, config.width, config.height);
+    println!("Tiempo por frame:   {:.2} ms", elapsed.as_secs_f64() * 1000.0 / frames);
+    match (start_memory, end_memory) {
+        (Some((start_allocations, start_bytes)), Some((end_allocations, end_bytes))) => {
+            println!("Reservas por frame: {:.0}", (end_allocations - start_allocations) as f64 / frames);
+            println!("Memoria por frame:  {:.2} MB", (end_bytes - start_bytes) as f64 / frames / (1024.0 * 1024.0));
+        }
+        _ => println!("Memoria por frame:  sin medir (compilar con --features alloc-counter)"),
     }
+}
+
+// (reservas, bytes reservados) desde el inicio, si el allocator que cuenta está instalado
+#[cfg(feature = "alloc-counter")]
+fn allocation_snapshot() -> Option<(usize, usize)> {
+    Some(alloc_counter::snapshot())
+}
+
+#[cfg(not(feature = "alloc-counter"))]
+fn allocation_snapshot() -> Option<(usize, usize)> {
+    None
+}
 
-    let planet_types = [
-        PlanetType::Rocky,   // Mercurio
-        PlanetType::Rocky,   // Venus
-        PlanetType::Normal,  // Tierra
-        PlanetType::Rocky,   // Marte
-        PlanetType::Gaseous, // Júpiter
-    ];
-
-    let colors = [
-        0x8C7853,  // Mercurio - gris marrón
-        0xFFC649,  // Venus - amarillo
-        0x4169E1,  // Tierra - azul
-        0xCD5C5C,  // Marte - rojo
-        0xDAA520,  // Júpiter - dorado/naranja
-    ];
+fn run_window(config: &Config) {
+    let window_width = config.width;
+    let window_height = config.height;
+    let framebuffer_width = config.width;
+    let framebuffer_height = config.height;
+    let frame_delay = Duration::from_millis(16);
+
+    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height, config.antialiasing);
+    let mut window = Window::new(
+        "Sistema Solar 3D - Rust Graphics",
+        window_width,
+        window_height,
+        WindowOptions::default(),
+    )
+    .unwrap();
+
+    window.set_position(100, 100);
+    framebuffer.set_background_color(0x000011);
+
+    let mut scene = Scene::new(config);
+    let mut camera = create_camera();
+    let mut settings = config.render;
+    let mut post_process = PostProcessChain::from_config(config);
 
     let start_time = Instant::now();
     
@@ -285,8 +616,12 @@ fn main() {
     println!("  J/L: Mover nave izquierda/derecha");
     println!("  U/O: Mover nave cerca/lejos");
     println!("\nWarp:");
-    println!("  1-5: Warp a planetas");
+    println!("  1-6: Warp a planetas");
     println!("  0: Warp al sol");
+    println!("\nGráficos:");
+    println!("  H: Activar/Desactivar sombras (eclipses)");
+    println!("  T: Cambiar tone mapping (Reinhard/ACES)");
+    println!("  B: Activar/Desactivar post-procesado (bloom)");
     println!("\nESC: Salir");
 
     while window.is_open() {
@@ -340,22 +675,22 @@ fn main() {
         
         // Controles de la nave espacial
         if window.is_key_down(Key::I) {
-            spaceship.adjust_offset(0.0, 1.0, 0.0);  // Arriba
+            scene.spaceship.adjust_offset(0.0, 1.0, 0.0);  // Arriba
         }
         if window.is_key_down(Key::K) {
-            spaceship.adjust_offset(0.0, -1.0, 0.0);  // Abajo
+            scene.spaceship.adjust_offset(0.0, -1.0, 0.0);  // Abajo
         }
         if window.is_key_down(Key::J) {
-            spaceship.adjust_offset(-1.0, 0.0, 0.0);  // Izquierda
+            scene.spaceship.adjust_offset(-1.0, 0.0, 0.0);  // Izquierda
         }
         if window.is_key_down(Key::L) {
-            spaceship.adjust_offset(1.0, 0.0, 0.0);  // Derecha
+            scene.spaceship.adjust_offset(1.0, 0.0, 0.0);  // Derecha
         }
         if window.is_key_down(Key::U) {
-            spaceship.adjust_offset(0.0, 0.0, -1.0);  // Más cerca
+            scene.spaceship.adjust_offset(0.0, 0.0, -1.0);  // Más cerca
         }
         if window.is_key_down(Key::O) {
-            spaceship.adjust_offset(0.0, 0.0, 1.0);  // Más lejos
+            scene.spaceship.adjust_offset(0.0, 0.0, 1.0);  // Más lejos
         }
         
         // Toggle modo 3D
@@ -363,8 +698,29 @@ fn main() {
             mode_3d = !mode_3d;
             println!("Modo 3D: {}", if mode_3d { "ACTIVADO" } else { "DESACTIVADO" });
         }
+
+        // Toggle sombras
+        if window.is_key_pressed(Key::H, minifb::KeyRepeat::No) {
+            settings.shadows = !settings.shadows;
+            println!("Sombras: {}", if settings.shadows { "ACTIVADAS" } else { "DESACTIVADAS" });
+        }
+
+        // Alternar operador de tone mapping
+        if window.is_key_pressed(Key::T, minifb::KeyRepeat::No) {
+            settings.tone_mapping = match settings.tone_mapping {
+                ToneMapping::Reinhard => ToneMapping::Aces,
+                ToneMapping::Aces => ToneMapping::Reinhard,
+            };
+            println!("Tone mapping: {:?}", settings.tone_mapping);
+        }
+
+        // Toggle post-procesado
+        if window.is_key_pressed(Key::B, minifb::KeyRepeat::No) {
+            settings.post_processing = !settings.post_processing;
+            println!("Post-procesado: {}", if settings.post_processing { "ACTIVADO" } else { "DESACTIVADO" });
+        }
         
-        // Instant warp a planetas (teclas 1-5)
+        // Instant warp a planetas (teclas 1-6)
         if window.is_key_pressed(Key::Key1, minifb::KeyRepeat::No) {
             warp_target_index = Some(0);
             warp_in_progress = true;
@@ -390,17 +746,22 @@ fn main() {
             warp_in_progress = true;
             println!("Warping a Júpiter...");
         }
+        if window.is_key_pressed(Key::Key6, minifb::KeyRepeat::No) {
+            warp_target_index = Some(5);
+            warp_in_progress = true;
+            println!("Warping a Saturno...");
+        }
         // Warp al sol
         if window.is_key_pressed(Key::Key0, minifb::KeyRepeat::No) {
-            camera.warp_to(sun.translation, 200.0);
+            camera.warp_to(scene.sun.translation, 200.0);
             println!("Warping al Sol...");
         }
         
         // Procesar warp animado
         if warp_in_progress {
             if let Some(idx) = warp_target_index {
-                if idx < planets.len() {
-                    let target = planets[idx].translation;
+                if idx < scene.planets.len() {
+                    let target = scene.planets[idx].translation;
                     let distance = 150.0;
                     let completed = camera.animated_warp_to(target, distance, 0.05);
                     if completed {
@@ -413,133 +774,10 @@ fn main() {
         }
 
         // ===== ACTUALIZAR PLANETAS =====
-        for planet in &mut planets {
-            planet.update();
-        }
-        sun.rotation.y += sun.rotation_speed;
+        scene.update();
 
         // ===== RENDERIZADO =====
-        framebuffer.clear();
-
-        // Crear matrices de transformación
-        let view_matrix = camera.get_view_matrix();
-        let projection_matrix = camera.get_projection_matrix(
-            window_width as f32,
-            window_height as f32
-        );
-        let viewport_matrix = create_viewport_matrix(
-            framebuffer_width as f32,
-            framebuffer_height as f32
-        );
-
-        let orbit_model_matrix = Mat4::new(
-            1.0, 0.0, 0.0, 0.0,
-            0.0, 1.0, 0.0, 0.0,
-            0.0, 0.0, 1.0, 0.0,
-            0.0, 0.0, 0.0, 1.0,
-        );
-
-        let orbit_uniforms = Uniforms {
-            model_matrix: orbit_model_matrix,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-        };
-
-        for ring in &orbit_vertex_rings {
-            // 1) Transformar cada vértice de la órbita a espacio de pantalla
-            let mut transformed_ring: Vec<Vertex> = Vec::with_capacity(ring.len());
-            for v in ring {
-                transformed_ring.push(vertex_shader(v, &orbit_uniforms));
-            }
-
-            // 2) Generar fragments uniendo vértices consecutivos con líneas
-            let mut orbit_fragments = Vec::new();
-            let n = transformed_ring.len();
-            for i in 0..n {
-                let a = &transformed_ring[i];
-                let b = &transformed_ring[(i + 1) % n]; // cerrar el círculo
-                orbit_fragments.extend(line(a, b));
-            }
-
-            // 3) Pintar los fragments en el framebuffer
-            for fragment in orbit_fragments {
-                let x = fragment.position.x as usize;
-                let y = fragment.position.y as usize;
-
-                if x < framebuffer_width && y < framebuffer_height {
-                    framebuffer.set_current_color(0x666666); // color final de las órbitas
-                    framebuffer.point(x, y, fragment.depth);
-                }
-            }
-        }
-
-        // Renderizar el sol
-        let sun_model_matrix = create_model_matrix(sun.translation, sun.scale, sun.rotation);
-        let sun_uniforms = Uniforms {
-            model_matrix: sun_model_matrix,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-        };
-        
-        render(
-            &mut framebuffer,
-            &sun_uniforms,
-            &vertex_arrays,
-            0xFFDD00,
-            PlanetType::Sun,
-            time,
-            sun.translation
-        );
-
-        // Renderizar planetas
-        for (i, planet) in planets.iter().enumerate() {
-            let model_matrix = create_model_matrix(
-                planet.translation,
-                planet.scale,
-                planet.rotation
-            );
-            let uniforms = Uniforms {
-                model_matrix,
-                view_matrix,
-                projection_matrix,
-                viewport_matrix,
-            };
-            render(
-                &mut framebuffer,
-                &uniforms,
-                &vertex_arrays,
-                colors[i],
-                planet_types[i],
-                time,
-                sun.translation
-            );
-        }
-        
-        // Renderizar nave espacial (al final para que se vea encima)
-        let spaceship_position = spaceship.get_world_position(&camera);
-        let spaceship_rotation = spaceship.get_world_rotation(&camera);
-        let spaceship_model_matrix = create_model_matrix(
-            spaceship_position,
-            spaceship.scale,
-            spaceship_rotation
-        );
-        let spaceship_uniforms = Uniforms {
-            model_matrix: spaceship_model_matrix,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
-        };
-        render(
-            &mut framebuffer,
-            &spaceship_uniforms,
-            &spaceship_vertices,
-            0xCCCCCC,  // Gris metálico
-            PlanetType::Spaceship,
-            time,
-            sun.translation
-        );
+        render_scene(&mut framebuffer, &scene, &camera, &settings, &mut post_process, time);
 
         window
             .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
@@ -547,4 +785,28 @@ fn main() {
 
         std::thread::sleep(frame_delay);
     }
-}
\ No newline at end of file
+}
+
+fn main() {
+    let config = match Config::from_args() {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            Config::print_usage();
+            std::process::exit(1);
+        }
+    };
+
+    if config.help {
+        Config::print_usage();
+        return;
+    }
+
+    if config.bench {
+        run_bench(&config);
+    } else if config.headless {
+        run_headless(&config);
+    } else {
+        run_window(&config);
+    }
+}
@@ -0,0 +1,87 @@
+use nalgebra_glm::{dot, Vec3};
+use crate::color::LinearColor;
+use crate::fragment::Fragment;
+use crate::shadows::sun_visibility;
+use crate::Uniforms;
+
+// Propiedades de iluminación de una superficie (modelo Blinn-Phong)
+#[derive(Clone, Copy, Debug)]
+pub struct Material {
+    pub ambient: f32,     // fracción del albedo visible en el lado oscuro
+    pub diffuse: f32,     // peso de la luz difusa del sol
+    pub specular: LinearColor,  // color del brillo especular; negro = superficie mate
+    pub shininess: f32,   // exponente especular: más alto = brillo más pequeño y nítido
+    pub emissive: LinearColor,  // luz propia, independiente del sol
+}
+
+impl Default for Material {
+    // Superficie mate, ambiente + difusa. Los valores son lineales: un ambiente de 0.04
+    // se ve como el 0.2 original después de la codificación sRGB.
+    fn default() -> Self {
+        Material {
+            ambient: 0.04,
+            diffuse: 0.8,
+            specular: LinearColor::black(),
+            shininess: 1.0,
+            emissive: LinearColor::black(),
+        }
+    }
+}
+
+impl Material {
+    // Material que solo emite luz (el sol)
+    pub fn emissive(color: LinearColor) -> Self {
+        Material {
+            ambient: 0.0,
+            diffuse: 0.0,
+            emissive: color,
+            ..Material::default()
+        }
+    }
+
+    // Iluminar un color base con el sol como fuente de luz y la cámara como observador
+    pub fn shade(&self, albedo: LinearColor, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        self.shade_with_normal(albedo, fragment.normal, fragment, uniforms)
+    }
+
+    // Igual que shade, con una normal propia (por ejemplo perturbada por un mapa de normales)
+    pub fn shade_with_normal(&self, albedo: LinearColor, normal: Vec3, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+
+        // Vectores desde el fragmento HACIA el sol y HACIA la cámara
+        let to_light = (uniforms.sun_position - fragment.world_position).normalize();
+        let to_camera = (uniforms.camera_position - fragment.world_position).normalize();
+
+        // Dot product: positivo cuando la normal apunta hacia la luz
+        let n_dot_l = dot(&normal, &to_light).max(0.0);
+
+        // Sombra de otros cuerpos (eclipses); solo importa en el lado iluminado
+        let mut visibility = if uniforms.shadows && n_dot_l > 0.0 {
+            sun_visibility(
+                &fragment.world_position,
+                &uniforms.sun_position,
+                uniforms.sun_radius,
+                uniforms.occluders,
+            )
+        } else {
+            1.0
+        };
+
+        // Sombra de la capa de nubes del propio planeta
+        if let Some(clouds) = &uniforms.clouds {
+            if uniforms.shadows && n_dot_l > 0.0 && visibility > 0.0 {
+                visibility *= clouds.shader.transmittance(clouds, &fragment.world_position, &uniforms.sun_position, uniforms.time);
+            }
+        }
+
+        let mut color = albedo * (self.ambient + self.diffuse * n_dot_l * visibility);
+
+        // Blinn-Phong: brillo según el vector medio entre luz y vista
+        if n_dot_l > 0.0 && visibility > 0.0 && !self.specular.is_black() {
+            let half_vector = (to_light + to_camera).normalize();
+            let specular = dot(&normal, &half_vector).max(0.0).powf(self.shininess);
+            color = color + self.specular * (specular * visibility);
+        }
+
+        color + self.emissive
+    }
+}
@@ -0,0 +1,138 @@
+use nalgebra_glm::Vec3;
+use std::collections::HashMap;
+
+use crate::vertex::Vertex;
+
+// Malla indexada: cada vértice único se guarda una vez y los triángulos lo referencian
+// por índice (de a tres en `indices`). Los límites se calculan al crearla.
+pub struct Mesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub bounds: Bounds,
+}
+
+// Límites en espacio de objeto: caja alineada a los ejes y esfera envolvente
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min: Vec3,
+    pub max: Vec3,
+    pub center: Vec3,  // centro de la esfera (el de la caja)
+    pub radius: f32,   // distancia al vértice más lejano desde `center`
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+        let bounds = Bounds::from_vertices(&vertices);
+        Mesh { vertices, indices, bounds }
+    }
+
+    // Indexar una lista de triángulos sueltos: los vértices con exactamente los mismos
+    // atributos se unen en uno solo
+    pub fn from_triangles(triangles: Vec<Vertex>) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::with_capacity(triangles.len());
+        let mut unique: HashMap<[u32; 12], u32> = HashMap::new();
+
+        for vertex in triangles {
+            let key = [
+                vertex.position.x, vertex.position.y, vertex.position.z,
+                vertex.normal.x, vertex.normal.y, vertex.normal.z,
+                vertex.tex_coords.x, vertex.tex_coords.y,
+                vertex.tangent.x, vertex.tangent.y, vertex.tangent.z, vertex.tangent.w,
+            ].map(f32::to_bits);
+
+            let index = *unique.entry(key).or_insert_with(|| {
+                vertices.push(vertex);
+                vertices.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+
+        Mesh::new(vertices, indices)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    // Vértices de cada triángulo
+    pub fn triangles(&self) -> impl Iterator<Item = [&Vertex; 3]> {
+        self.indices.chunks_exact(3).map(|triangle| {
+            [
+                &self.vertices[triangle[0] as usize],
+                &self.vertices[triangle[1] as usize],
+                &self.vertices[triangle[2] as usize],
+            ]
+        })
+    }
+}
+
+impl Bounds {
+    fn from_vertices(vertices: &[Vertex]) -> Self {
+        if vertices.is_empty() {
+            return Bounds { min: Vec3::zeros(), max: Vec3::zeros(), center: Vec3::zeros(), radius: 0.0 };
+        }
+
+        let (min, max) = vertices.iter().fold(
+            (Vec3::repeat(f32::INFINITY), Vec3::repeat(f32::NEG_INFINITY)),
+            |(min, max), vertex| (min.inf(&vertex.position), max.sup(&vertex.position)),
+        );
+        let center = (min + max) * 0.5;
+        let radius = vertices
+            .iter()
+            .map(|vertex| (vertex.position - center).magnitude())
+            .fold(0.0, f32::max);
+
+        Bounds { min, max, center, radius }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec2;
+
+    fn vertex(x: f32, y: f32, z: f32) -> Vertex {
+        Vertex::new(Vec3::new(x, y, z), Vec3::new(0.0, 0.0, 1.0), Vec2::new(x, y))
+    }
+
+    #[test]
+    fn identical_vertices_are_shared() {
+        // Un cuadrado como dos triángulos sueltos: la diagonal se repite
+        let mesh = Mesh::from_triangles(vec![
+            vertex(0.0, 0.0, 0.0), vertex(1.0, 0.0, 0.0), vertex(1.0, 1.0, 0.0),
+            vertex(0.0, 0.0, 0.0), vertex(1.0, 1.0, 0.0), vertex(0.0, 1.0, 0.0),
+        ]);
+
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert!(mesh.indices.len() > mesh.vertices.len());
+    }
+
+    #[test]
+    fn vertices_with_different_attributes_stay_apart() {
+        // Misma posición con otra coordenada de textura (como en la costura de una esfera)
+        let mut seam = vertex(0.0, 0.0, 0.0);
+        seam.tex_coords = Vec2::new(1.0, 0.0);
+        let mesh = Mesh::from_triangles(vec![
+            vertex(0.0, 0.0, 0.0), vertex(1.0, 0.0, 0.0), vertex(1.0, 1.0, 0.0),
+            seam, vertex(1.0, 1.0, 0.0), vertex(0.0, 1.0, 0.0),
+        ]);
+
+        assert_eq!(mesh.vertices.len(), 5);
+    }
+
+    #[test]
+    fn bounds_match_the_input_extents() {
+        let mesh = Mesh::from_triangles(vec![
+            vertex(-2.0, 0.0, 1.0), vertex(3.0, -1.0, 0.0), vertex(0.0, 4.0, -5.0),
+        ]);
+
+        assert_eq!(mesh.bounds.min, Vec3::new(-2.0, -1.0, -5.0));
+        assert_eq!(mesh.bounds.max, Vec3::new(3.0, 4.0, 1.0));
+        assert_eq!(mesh.bounds.center, Vec3::new(0.5, 1.5, -2.0));
+
+        // El vértice más lejano del centro es el primero: (-2.5, -1.5, 3)
+        assert!((mesh.bounds.radius - 17.5f32.sqrt()).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,49 @@
+use nalgebra_glm::Vec3;
+
+// Hash entero -> [0, 1] para los vértices de la grilla 3D
+pub fn hash3(x: i32, y: i32, z: i32) -> f32 {
+    let mut h = x.wrapping_mul(374761393) ^ y.wrapping_mul(668265263) ^ z.wrapping_mul(1274126177);
+    h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+    h ^= h >> 16;
+    (h & 0x00FF_FFFF) as f32 / 0x00FF_FFFF as f32
+}
+
+// Value noise 3D con interpolación suave; continuo sobre la superficie de la esfera
+pub fn noise3(p: Vec3) -> f32 {
+    let (xi, yi, zi) = (p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32);
+    let (fx, fy, fz) = (p.x - p.x.floor(), p.y - p.y.floor(), p.z - p.z.floor());
+
+    // Curva de suavizado (smoothstep) para evitar bordes de la grilla
+    let (u, v, w) = (
+        fx * fx * (3.0 - 2.0 * fx),
+        fy * fy * (3.0 - 2.0 * fy),
+        fz * fz * (3.0 - 2.0 * fz),
+    );
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let x00 = lerp(hash3(xi, yi, zi), hash3(xi + 1, yi, zi), u);
+    let x10 = lerp(hash3(xi, yi + 1, zi), hash3(xi + 1, yi + 1, zi), u);
+    let x01 = lerp(hash3(xi, yi, zi + 1), hash3(xi + 1, yi, zi + 1), u);
+    let x11 = lerp(hash3(xi, yi + 1, zi + 1), hash3(xi + 1, yi + 1, zi + 1), u);
+
+    lerp(lerp(x00, x10, v), lerp(x01, x11, v), w)
+}
+
+pub fn fbm3(p: Vec3, octaves: i32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..octaves {
+        value += amplitude * noise3(p * frequency);
+        frequency *= 2.0;
+        amplitude *= 0.5;
+    }
+    value
+}
+
+// Transición suave de 0 (x <= edge0) a 1 (x >= edge1)
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -1,15 +1,16 @@
-use tobj;
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+use crate::mesh::Mesh;
 use crate::vertex::Vertex;
 
 pub struct Obj {
-    meshes: Vec<Mesh>,
+    meshes: Vec<ObjMesh>,
 }
 
-struct Mesh {
+struct ObjMesh {
     vertices: Vec<Vec3>,
     normals: Vec<Vec3>,
     texcoords: Vec<Vec2>,
+    tangents: Vec<Vec4>,
     indices: Vec<u32>,
 }
 
@@ -23,16 +24,21 @@ impl Obj {
 
         let meshes = models.into_iter().map(|model| {
             let mesh = model.mesh;
-            Mesh {
-                vertices: mesh.positions.chunks(3)
-                    .map(|v| Vec3::new(v[0], v[1], v[2]))  // SIN invertir Y y Z
-                    .collect(),
-                normals: mesh.normals.chunks(3)
-                    .map(|n| Vec3::new(n[0], n[1], n[2]))  // SIN invertir normales
-                    .collect(),
-                texcoords: mesh.texcoords.chunks(2)
-                    .map(|t| Vec2::new(t[0], 1.0 - t[1]))
-                    .collect(),
+            let vertices: Vec<Vec3> = mesh.positions.chunks(3)
+                .map(|v| Vec3::new(v[0], v[1], v[2]))  // SIN invertir Y y Z
+                .collect();
+            let normals: Vec<Vec3> = mesh.normals.chunks(3)
+                .map(|n| Vec3::new(n[0], n[1], n[2]))  // SIN invertir normales
+                .collect();
+            let texcoords: Vec<Vec2> = mesh.texcoords.chunks(2)
+                .map(|t| Vec2::new(t[0], 1.0 - t[1]))
+                .collect();
+            let tangents = compute_tangents(&vertices, &normals, &texcoords, &mesh.indices);
+            ObjMesh {
+                vertices,
+                normals,
+                texcoords,
+                tangents,
                 indices: mesh.indices,
             }
         }).collect();
@@ -40,23 +46,107 @@ impl Obj {
         Ok(Obj { meshes })
     }
 
-    pub fn get_vertex_array(&self) -> Vec<Vertex> {
+    // Una sola malla indexada con todas las del archivo; los índices de cada una se
+    // desplazan según los vértices que ya se agregaron
+    pub fn get_mesh(&self) -> Mesh {
         let mut vertices = Vec::new();
+        let mut indices = Vec::new();
 
         for mesh in &self.meshes {
-            for &index in &mesh.indices {
-                let position = mesh.vertices[index as usize];
-                let normal = mesh.normals.get(index as usize)
+            let offset = vertices.len() as u32;
+            for (index, &position) in mesh.vertices.iter().enumerate() {
+                let normal = mesh.normals.get(index)
                     .cloned()
                     .unwrap_or(Vec3::new(0.0, 1.0, 0.0));
-                let tex_coords = mesh.texcoords.get(index as usize)
+                let tex_coords = mesh.texcoords.get(index)
                     .cloned()
                     .unwrap_or(Vec2::new(0.0, 0.0));
 
-                vertices.push(Vertex::new(position, normal, tex_coords));
+                let mut vertex = Vertex::new(position, normal, tex_coords);
+                if let Some(&tangent) = mesh.tangents.get(index) {
+                    vertex.tangent = tangent;
+                }
+                vertices.push(vertex);
             }
+            indices.extend(mesh.indices.iter().map(|&index| index + offset));
         }
 
-        vertices
+        Mesh::new(vertices, indices)
     }
-}
\ No newline at end of file
+}
+
+// Tangentes por vértice a partir de las coordenadas de textura: la tangente apunta hacia
+// +u sobre la superficie y la bitangente hacia +v. Se acumulan las de cada triángulo que
+// comparte el vértice y se ortogonalizan contra la normal (Gram-Schmidt); w guarda si la
+// bitangente es cross(normal, tangente) o la opuesta (UV espejadas).
+fn compute_tangents(vertices: &[Vec3], normals: &[Vec3], texcoords: &[Vec2], indices: &[u32]) -> Vec<Vec4> {
+    if texcoords.len() < vertices.len() {
+        return Vec::new();
+    }
+
+    let mut tangents = vec![Vec3::zeros(); vertices.len()];
+    let mut bitangents = vec![Vec3::zeros(); vertices.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+        let (edge1, edge2) = (vertices[i1] - vertices[i0], vertices[i2] - vertices[i0]);
+        let (duv1, duv2) = (texcoords[i1] - texcoords[i0], texcoords[i2] - texcoords[i0]);
+
+        let determinant = duv1.x * duv2.y - duv2.x * duv1.y;
+        if determinant.abs() < 1e-12 {
+            continue;  // UV degeneradas (por ejemplo en los polos)
+        }
+        let r = 1.0 / determinant;
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) * r;
+        let bitangent = (edge2 * duv1.x - edge1 * duv2.x) * r;
+
+        for i in [i0, i1, i2] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+
+    (0..vertices.len())
+        .map(|i| {
+            let normal = normals.get(i).cloned().unwrap_or(Vec3::new(0.0, 1.0, 0.0));
+            let tangent = tangents[i] - normal * normal.dot(&tangents[i]);
+            let tangent = if tangent.magnitude_squared() > 1e-12 {
+                tangent.normalize()
+            } else {
+                // Sin información de UV: cualquier dirección perpendicular a la normal
+                let axis = if normal.x.abs() < 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+                (axis - normal * normal.dot(&axis)).normalize()
+            };
+            let handedness = if normal.cross(&tangent).dot(&bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            Vec4::new(tangent.x, tangent.y, tangent.z, handedness)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn planar_quad_gets_tangent_along_u() {
+        // Cuadrado en el plano XY mirando a +Z, con u a lo largo de X y v a lo largo de Y
+        let vertices = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ];
+        let normals = [Vec3::new(0.0, 0.0, 1.0); 4];
+        let texcoords = vertices.map(|position| Vec2::new(position.x, position.y));
+        let indices = [0, 1, 2, 0, 2, 3];
+
+        let tangents = compute_tangents(&vertices, &normals, &texcoords, &indices);
+        assert_eq!(tangents.len(), 4);
+        for (tangent, normal) in tangents.iter().zip(&normals) {
+            assert!((tangent.xyz() - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 1e-6, "{:?}", tangent);
+            assert!(tangent.xyz().dot(normal).abs() < 1e-6);
+            // +v va hacia +Y = cross(normal, tangente): sin espejar
+            assert_eq!(tangent.w, 1.0);
+        }
+    }
+}
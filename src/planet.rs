@@ -1,8 +1,14 @@
 use nalgebra_glm::Vec3;
+use crate::atmosphere::Atmosphere;
+use crate::clouds::CloudLayer;
+use crate::color::LinearColor;
+use crate::ring::Ring;
+use crate::shaders::FragmentShader;
 
 pub struct Planet {
     pub translation: Vec3,
     pub rotation: Vec3,
+    pub tilt: f32,  // inclinación del eje de giro (Y del objeto) alrededor de X, en radianes
     pub scale: f32,
     pub orbit_speed: f32,
     pub rotation_speed: f32,
@@ -10,10 +16,22 @@ pub struct Planet {
     pub orbit_angle: f32,
     pub center_x: f32,
     pub center_y: f32,
+    pub shader: Box<dyn FragmentShader>,  // material de la superficie
+    pub atmosphere: Option<Atmosphere>,
+    pub clouds: Option<CloudLayer>,
+    pub rings: Option<Ring>,
+    pub orbit_color: LinearColor,  // color de la línea de la órbita (antes de apagarlo)
 }
 
 impl Planet {
-    pub fn new(orbit_radius: f32, scale: f32, orbit_speed: f32, rotation_speed: f32, initial_angle: f32) -> Self {
+    pub fn new(
+        orbit_radius: f32,
+        scale: f32,
+        orbit_speed: f32,
+        rotation_speed: f32,
+        initial_angle: f32,
+        shader: Box<dyn FragmentShader>,
+    ) -> Self {
         let center_x = 0.0;  // Centro en el origen del mundo
         let center_y = 0.0;
         
@@ -26,6 +44,7 @@ impl Planet {
         Planet {
             translation,
             rotation: Vec3::new(0.0, 0.0, 0.0),
+            tilt: 0.0,
             scale,
             orbit_speed,
             rotation_speed,
@@ -33,8 +52,38 @@ impl Planet {
             orbit_angle: initial_angle,
             center_x,
             center_y,
+            shader,
+            atmosphere: None,
+            clouds: None,
+            rings: None,
+            orbit_color: LinearColor::new(0.5, 0.5, 0.5),
         }
     }
+
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    pub fn with_clouds(mut self, clouds: CloudLayer) -> Self {
+        self.clouds = Some(clouds);
+        self
+    }
+
+    pub fn with_tilt(mut self, tilt: f32) -> Self {
+        self.tilt = tilt;
+        self
+    }
+
+    pub fn with_rings(mut self, rings: Ring) -> Self {
+        self.rings = Some(rings);
+        self
+    }
+
+    pub fn with_orbit_color(mut self, color: LinearColor) -> Self {
+        self.orbit_color = color;
+        self
+    }
     
     pub fn update(&mut self) {
         // Actualizar ángulo de órbita
@@ -46,5 +95,10 @@ impl Planet {
         
         // Actualizar rotación del planeta
         self.rotation.y += self.rotation_speed;
+
+        // Las nubes giran con su propia velocidad
+        if let Some(clouds) = &mut self.clouds {
+            clouds.update();
+        }
     }
 }
\ No newline at end of file
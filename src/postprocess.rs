@@ -0,0 +1,193 @@
+use crate::color::LinearColor;
+use crate::config::Config;
+use crate::framebuffer::Framebuffer;
+
+// Efecto de post-procesado: trabaja sobre el buffer HDR completo, después de
+// rasterizar la escena y antes del tone mapping
+pub trait PostEffect {
+    fn apply(&mut self, image: &mut [LinearColor], width: usize, height: usize);
+}
+
+// Lista de efectos que se aplican en orden sobre el framebuffer
+#[derive(Default)]
+pub struct PostProcessChain {
+    effects: Vec<Box<dyn PostEffect>>,
+}
+
+impl PostProcessChain {
+    // Cadena según las opciones de la línea de comandos. Siempre se arma completa: si se
+    // aplica o no lo decide RenderSettings::post_processing (--no-bloom, tecla B)
+    pub fn from_config(config: &Config) -> Self {
+        let mut chain = PostProcessChain::default();
+        // Con SSAA la imagen es más grande: reducir más para que el halo mida lo mismo
+        let settings = BloomSettings {
+            downsample: config.bloom_settings.downsample * config.antialiasing.ssaa,
+            ..config.bloom_settings
+        };
+        chain.push(Box::new(Bloom::new(settings)));
+        chain
+    }
+
+    pub fn push(&mut self, effect: Box<dyn PostEffect>) {
+        self.effects.push(effect);
+    }
+
+    pub fn apply(&mut self, framebuffer: &mut Framebuffer) {
+        for effect in &mut self.effects {
+            effect.apply(&mut framebuffer.image, framebuffer.width, framebuffer.height);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BloomSettings {
+    pub threshold: f32,    // luminancia a partir de la cual un píxel brilla
+    pub intensity: f32,    // peso del brillo al sumarlo sobre la imagen
+    pub downsample: usize, // el desenfoque se hace a 1/downsample de la resolución
+    pub sigma: f32,        // desviación del gaussiano, en píxeles de la imagen reducida
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        BloomSettings {
+            threshold: 0.6,
+            intensity: 1.0,
+            downsample: 8,
+            sigma: 4.0,
+        }
+    }
+}
+
+// Bloom: extrae las zonas brillantes (sol, materiales emisivos, reflejos), las desenfoca
+// con un gaussiano separable a resolución reducida y suma el resultado a la imagen
+pub struct Bloom {
+    settings: BloomSettings,
+    kernel: Vec<f32>,         // mitad del kernel gaussiano: kernel[0] es el centro
+    bright: Vec<LinearColor>, // buffers a resolución reducida, reutilizados entre frames
+    scratch: Vec<LinearColor>,
+}
+
+impl Bloom {
+    pub fn new(settings: BloomSettings) -> Self {
+        let settings = BloomSettings {
+            downsample: settings.downsample.max(1),
+            ..settings
+        };
+
+        // Pesos normalizados hasta 3 sigmas
+        let radius = (settings.sigma * 3.0).ceil().max(1.0) as usize;
+        let mut kernel: Vec<f32> = (0..=radius)
+            .map(|i| (-((i * i) as f32) / (2.0 * settings.sigma * settings.sigma)).exp())
+            .collect();
+        let total = kernel[0] + 2.0 * kernel[1..].iter().sum::<f32>();
+        for weight in &mut kernel {
+            *weight /= total;
+        }
+
+        Bloom {
+            settings,
+            kernel,
+            bright: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    // 1) Bright pass: promedio de cada bloque de downsample x downsample píxeles,
+    //    conservando solo la parte de la luminancia que supera el umbral
+    fn bright_pass(&mut self, image: &[LinearColor], width: usize, height: usize, small_width: usize, small_height: usize) {
+        let factor = self.settings.downsample;
+        let threshold = self.settings.threshold;
+
+        for sy in 0..small_height {
+            for sx in 0..small_width {
+                let mut sum = LinearColor::black();
+                let mut count = 0;
+                for y in (sy * factor)..((sy + 1) * factor).min(height) {
+                    for x in (sx * factor)..((sx + 1) * factor).min(width) {
+                        sum = sum + image[y * width + x];
+                        count += 1;
+                    }
+                }
+                let average = sum * (1.0 / count as f32);
+
+                let luminance = average.luminance();
+                let weight = if luminance > threshold {
+                    (luminance - threshold) / luminance
+                } else {
+                    0.0
+                };
+                self.bright[sy * small_width + sx] = average * weight;
+            }
+        }
+    }
+
+    // 2) Gaussiano separable: horizontal de `bright` a `scratch`, vertical de vuelta a `bright`
+    fn blur(&mut self, width: usize, height: usize) {
+        let radius = self.kernel.len() as i32 - 1;
+
+        for y in 0..height {
+            let row = y * width;
+            for x in 0..width {
+                let mut sum = self.bright[row + x] * self.kernel[0];
+                for offset in 1..=radius {
+                    let left = (x as i32 - offset).max(0) as usize;
+                    let right = (x as i32 + offset).min(width as i32 - 1) as usize;
+                    sum = sum + (self.bright[row + left] + self.bright[row + right]) * self.kernel[offset as usize];
+                }
+                self.scratch[row + x] = sum;
+            }
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = self.scratch[y * width + x] * self.kernel[0];
+                for offset in 1..=radius {
+                    let up = (y as i32 - offset).max(0) as usize;
+                    let down = (y as i32 + offset).min(height as i32 - 1) as usize;
+                    sum = sum + (self.scratch[up * width + x] + self.scratch[down * width + x]) * self.kernel[offset as usize];
+                }
+                self.bright[y * width + x] = sum;
+            }
+        }
+    }
+
+    // Muestreo bilineal del buffer reducido para volver a resolución completa sin bloques
+    fn sample(&self, x: f32, y: f32, width: usize, height: usize) -> LinearColor {
+        let x = (x - 0.5).clamp(0.0, (width - 1) as f32);
+        let y = (y - 0.5).clamp(0.0, (height - 1) as f32);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+        let top = self.bright[y0 * width + x0].lerp(&self.bright[y0 * width + x1], tx);
+        let bottom = self.bright[y1 * width + x0].lerp(&self.bright[y1 * width + x1], tx);
+        top.lerp(&bottom, ty)
+    }
+}
+
+impl PostEffect for Bloom {
+    fn apply(&mut self, image: &mut [LinearColor], width: usize, height: usize) {
+        let factor = self.settings.downsample;
+        let small_width = width.div_ceil(factor);
+        let small_height = height.div_ceil(factor);
+
+        // Solo reserva memoria la primera vez (o si cambia la resolución)
+        let small_size = small_width * small_height;
+        self.bright.resize(small_size, LinearColor::black());
+        self.scratch.resize(small_size, LinearColor::black());
+
+        self.bright_pass(image, width, height, small_width, small_height);
+        self.blur(small_width, small_height);
+
+        // 3) Composición aditiva sobre la imagen original
+        let scale = 1.0 / factor as f32;
+        let intensity = self.settings.intensity;
+        for y in 0..height {
+            for x in 0..width {
+                let glow = self.sample((x as f32 + 0.5) * scale, (y as f32 + 0.5) * scale, small_width, small_height);
+                let pixel = &mut image[y * width + x];
+                *pixel = *pixel + glow * intensity;
+            }
+        }
+    }
+}
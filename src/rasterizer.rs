@@ -0,0 +1,192 @@
+use nalgebra_glm::{Vec2, Vec3};
+use rayon::prelude::*;
+
+use crate::color::LinearColor;
+use crate::fragment::{Fragment, FragmentSink};
+use crate::framebuffer::Framebuffer;
+use crate::triangle::{triangle, CullMode};
+use crate::vertex::Vertex;
+
+// Alto de cada tile en píxeles. Los tiles ocupan todo el ancho del framebuffer, así
+// cada hilo recibe un bloque contiguo de color y profundidad que puede escribir sin locks.
+pub const TILE_HEIGHT: usize = 16;
+
+// Región de pantalla (límites inclusivos) a la que se recorta la rasterización
+#[derive(Clone, Copy, Debug)]
+pub struct Tile {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+// Cómo se combina el color de un fragment con el que ya está en el framebuffer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlendMode {
+    Opaque,    // reemplaza el color
+    Alpha,     // destino * (1 - a) + fuente * a (atmósferas, nubes, anillos)
+    Additive,  // destino + fuente * a (brillos, escape de motores)
+}
+
+// Estado fijo del pipeline para un draw
+#[derive(Clone, Copy, Debug)]
+pub struct PipelineState {
+    pub cull_mode: CullMode,
+    pub blend_mode: BlendMode,
+    pub depth_write: bool,  // los objetos transparentes prueban profundidad pero no la escriben
+}
+
+impl PipelineState {
+    pub fn opaque(cull_mode: CullMode) -> Self {
+        PipelineState {
+            cull_mode,
+            blend_mode: BlendMode::Opaque,
+            depth_write: true,
+        }
+    }
+
+    pub fn transparent(blend_mode: BlendMode) -> Self {
+        PipelineState {
+            cull_mode: CullMode::Back,
+            blend_mode,
+            depth_write: false,
+        }
+    }
+}
+
+// Porción del framebuffer que pertenece a un tile; sombrea y escribe cada fragment
+// que pasa el z-test sin pasar por un Vec intermedio. Con MSAA el color calculado
+// una vez se copia a cada muestra cubierta.
+struct TileTarget<'a, S> {
+    colors: &'a mut [LinearColor],
+    depths: &'a mut [f32],
+    width: usize,
+    tile_y: usize,
+    sample_offsets: &'a [Vec2],
+    blend_mode: BlendMode,
+    depth_write: bool,
+    shader: &'a S,
+}
+
+impl<S> TileTarget<'_, S> {
+    fn sample_index(&self, x: usize, y: usize, sample: usize) -> usize {
+        ((y - self.tile_y) * self.width + x) * self.sample_offsets.len() + sample
+    }
+}
+
+impl<S: Fn(&Fragment) -> LinearColor> FragmentSink for TileTarget<'_, S> {
+    fn sample_offsets(&self) -> &[Vec2] {
+        self.sample_offsets
+    }
+
+    fn test_depth(&self, x: usize, y: usize, sample: usize, depth: f32) -> bool {
+        self.depths[self.sample_index(x, y, sample)] > depth
+    }
+
+    fn write(&mut self, fragment: Fragment) {
+        let x = fragment.position.x as usize;
+        let y = fragment.position.y as usize;
+        let color = (self.shader)(&fragment);
+
+        for (sample, offset) in self.sample_offsets.iter().enumerate() {
+            if fragment.coverage & (1 << sample) == 0 {
+                continue;
+            }
+
+            let index = self.sample_index(x, y, sample);
+            let destination = self.colors[index];
+            self.colors[index] = match self.blend_mode {
+                BlendMode::Opaque => color,
+                BlendMode::Alpha => destination.lerp(&color, color.a).with_alpha(destination.a),
+                BlendMode::Additive => destination + color * color.a,
+            };
+
+            if self.depth_write {
+                let delta = offset - Vec2::new(0.5, 0.5);
+                self.depths[index] = fragment.depth + fragment.depth_gradient.dot(&delta);
+            }
+        }
+    }
+}
+
+// Rasteriza triángulos (índices a `vertices`, ya proyectados a pantalla): los agrupa por tile y
+// reparte los tiles entre los hilos de rayon; cada uno hace el z-test y sombrea en su propio bloque.
+// Los fragments se procesan a medida que se generan, sin reservar memoria por fragment.
+pub fn rasterize<S>(
+    framebuffer: &mut Framebuffer,
+    vertices: &[Vertex],
+    triangles: &[[usize; 3]],
+    state: &PipelineState,
+    shader: S,
+) where
+    S: Fn(&Fragment) -> LinearColor + Sync,
+{
+    let width = framebuffer.width;
+    let height = framebuffer.height;
+    let tile_size = width * TILE_HEIGHT * framebuffer.samples;
+    if width == 0 || height == 0 || triangles.is_empty() {
+        return;
+    }
+
+    // 1) Binning: cada triángulo se agrega a los tiles que cubre su bounding box
+    let tile_count = height.div_ceil(TILE_HEIGHT);
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); tile_count];
+
+    for (index, tri) in triangles.iter().enumerate() {
+        let positions = tri.iter().map(|&i| vertices[i].transformed_position);
+        let (min, max) = positions.fold(
+            (Vec3::repeat(f32::INFINITY), Vec3::repeat(f32::NEG_INFINITY)),
+            |(min, max), p| (min.inf(&p), max.sup(&p)),
+        );
+
+        if max.x < 0.0 || max.y < 0.0 || min.x >= width as f32 || min.y >= height as f32 {
+            continue;
+        }
+
+        let first = min.y.floor().max(0.0) as usize / TILE_HEIGHT;
+        let last = (max.y.ceil() as usize).min(height - 1) / TILE_HEIGHT;
+        for bin in &mut bins[first..=last] {
+            bin.push(index);
+        }
+    }
+
+    // 2) Un trabajo por tile no vacío, con su porción del color buffer y del z-buffer
+    let jobs: Vec<_> = framebuffer.color_buffer
+        .chunks_mut(tile_size)
+        .zip(framebuffer.zbuffer.chunks_mut(tile_size))
+        .zip(bins.iter())
+        .enumerate()
+        .filter(|(_, (_, bin))| !bin.is_empty())
+        .map(|(tile_index, ((colors, depths), bin))| (tile_index, colors, depths, bin))
+        .collect();
+
+    let sample_offsets = framebuffer.sample_offsets.as_slice();
+
+    // 3) Rasterizar los tiles en paralelo (el pool de rayon se crea una sola vez)
+    jobs.into_par_iter().for_each(|(tile_index, colors, depths, bin)| {
+        let tile_y = tile_index * TILE_HEIGHT;
+        let tile = Tile {
+            min_x: 0,
+            min_y: tile_y as i32,
+            max_x: width as i32 - 1,
+            max_y: (tile_y + colors.len() / (width * sample_offsets.len())) as i32 - 1,
+        };
+
+        let mut target = TileTarget {
+            colors,
+            depths,
+            width,
+            tile_y,
+            sample_offsets,
+            blend_mode: state.blend_mode,
+            depth_write: state.depth_write,
+            shader: &shader,
+        };
+
+        for &index in bin {
+            let [i1, i2, i3] = triangles[index];
+            let (v1, v2, v3) = (&vertices[i1], &vertices[i2], &vertices[i3]);
+            triangle(v1, v2, v3, state.cull_mode, &tile, &mut target);
+        }
+    });
+}
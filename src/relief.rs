@@ -0,0 +1,180 @@
+use nalgebra_glm::{Vec2, Vec3};
+use std::f32::consts::PI;
+
+use crate::color::LinearColor;
+use crate::fragment::Fragment;
+use crate::noise::{hash3, noise3};
+use crate::sphere::equirect_direction;
+use crate::texture::Texture;
+
+// Mapa de normales en espacio tangente (convención OpenGL: rojo = +u, verde = hacia
+// arriba en la imagen, azul = afuera de la superficie). Se carga de una imagen o se
+// genera a partir de un campo de alturas procedural.
+pub struct NormalMap {
+    texture: Texture,
+    pub strength: f32,  // escala de la inclinación: 0 = superficie lisa, 1 = la del mapa
+}
+
+impl NormalMap {
+    pub fn load(path: &str) -> Result<Self, image::ImageError> {
+        Ok(NormalMap {
+            texture: Texture::load_data(path)?,
+            strength: 1.0,
+        })
+    }
+
+    // Generar un mapa equirectangular a partir de `height_fn`, que recibe la dirección
+    // desde el centro del planeta y devuelve la altura en radios del planeta.
+    // `exaggeration` multiplica las alturas para que el relieve se note a esta escala.
+    pub fn from_height<F: Fn(Vec3) -> f32>(width: usize, height: usize, exaggeration: f32, height_fn: F) -> Self {
+        // 1) Alturas en cada texel
+        let mut heights = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let u = (x as f32 + 0.5) / width as f32;
+                let v = (y as f32 + 0.5) / height as f32;
+                heights.push(height_fn(equirect_direction(Vec2::new(u, v))) * exaggeration);
+            }
+        }
+
+        // 2) Pendientes por diferencias centrales: U se repite (longitud), V se recorta (polos).
+        //    El largo de un texel sobre la esfera se achica hacia los polos.
+        let at = |x: i32, y: i32| {
+            let x = x.rem_euclid(width as i32) as usize;
+            let y = y.clamp(0, height as i32 - 1) as usize;
+            heights[y * width + x]
+        };
+        let step_v = PI / height as f32;
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height as i32 {
+            let latitude = PI / 2.0 - (y as f32 + 0.5) * step_v;
+            let step_u = 2.0 * PI * latitude.cos().max(0.05) / width as f32;
+
+            for x in 0..width as i32 {
+                let slope_u = (at(x + 1, y) - at(x - 1, y)) / (2.0 * step_u);
+                let slope_v = (at(x, y + 1) - at(x, y - 1)) / (2.0 * step_v);
+
+                // V crece hacia abajo en la imagen: "arriba" es -v
+                let normal = Vec3::new(-slope_u, slope_v, 1.0).normalize();
+                pixels.push(LinearColor::new(
+                    normal.x * 0.5 + 0.5,
+                    normal.y * 0.5 + 0.5,
+                    normal.z * 0.5 + 0.5,
+                ));
+            }
+        }
+
+        NormalMap {
+            texture: Texture::from_pixels(width, height, pixels),
+            strength: 1.0,
+        }
+    }
+
+    // Normal en espacio mundial perturbada por el mapa en la posición del fragment
+    pub fn perturb(&self, fragment: &Fragment) -> Vec3 {
+        let normal = fragment.normal;
+
+        // La tangente interpolada deja de ser perpendicular a la normal: Gram-Schmidt
+        let tangent = fragment.tangent.xyz();
+        let tangent = tangent - normal * normal.dot(&tangent);
+        if tangent.magnitude_squared() < 1e-12 {
+            return normal;
+        }
+        let tangent = tangent.normalize();
+        let handedness = if fragment.tangent.w < 0.0 { -1.0 } else { 1.0 };
+        let bitangent = normal.cross(&tangent) * handedness;  // hacia +v (abajo en la imagen)
+
+        let texel = self.texture.sample(fragment.tex_coords);
+        let x = (texel.r * 2.0 - 1.0) * self.strength;
+        let y = (texel.g * 2.0 - 1.0) * self.strength;
+        let z = texel.b * 2.0 - 1.0;
+
+        (tangent * x - bitangent * y + normal * z).normalize()
+    }
+}
+
+// Cráteres: en cada celda de una grilla 3D hay uno con centro y tamaño al azar.
+// Perfil: cuenco hundido, borde elevado y una caída suave hacia afuera, con una
+// profundidad de ~1/10 del diámetro. Devuelve la altura en radios del planeta.
+pub fn craters(p: Vec3, frequency: f32) -> f32 {
+    const MAX_RADIUS: f32 = 0.4;
+    const REACH: f32 = 1.6;  // el borde se desvanece a 1.6 radios del centro
+
+    let q = p * frequency;
+    let cell = Vec3::new(q.x.floor(), q.y.floor(), q.z.floor());
+    let local = q - cell;
+
+    // Solo las celdas vecinas cuyo cráter más grande podría llegar hasta q
+    let range = |t: f32| {
+        let first = if t < MAX_RADIUS * REACH { -1 } else { 0 };
+        let last = if t > 1.0 - MAX_RADIUS * REACH { 1 } else { 0 };
+        first..=last
+    };
+
+    let mut height = 0.0;
+    for dz in range(local.z) {
+        for dy in range(local.y) {
+            for dx in range(local.x) {
+                let (ix, iy, iz) = (cell.x as i32 + dx, cell.y as i32 + dy, cell.z as i32 + dz);
+                let center = Vec3::new(
+                    ix as f32 + hash3(ix, iy, iz),
+                    iy as f32 + hash3(iy + 31, iz, ix),
+                    iz as f32 + hash3(iz + 57, ix, iy),
+                );
+                // Muchos cráteres chicos y pocos grandes
+                let size = hash3(ix + 101, iy + 7, iz + 13);
+                let radius = 0.08 + (MAX_RADIUS - 0.08) * size * size;
+
+                let d = (q - center).magnitude() / radius;
+                if d > REACH {
+                    continue;
+                }
+                let bowl = if d < 1.0 { d * d - 1.0 } else { 0.0 };
+                let rim = 0.35 * (-((d - 1.0) / 0.25).powi(2)).exp();
+                height += (bowl + rim) * radius * 0.2;
+            }
+        }
+    }
+    height / frequency
+}
+
+// Ruido "ridged" en 0..1: crestas finas donde el ruido cruza su valor medio; con
+// varias octavas forma cordilleras (o crestas de hielo a frecuencias altas)
+pub fn ridges(p: Vec3, octaves: i32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..octaves {
+        let ridge = 1.0 - (noise3(p * frequency) * 2.0 - 1.0).abs();
+        value += amplitude * ridge * ridge;
+        frequency *= 2.0;
+        amplitude *= 0.5;
+    }
+    value
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec4;
+
+    #[test]
+    fn flat_height_field_keeps_the_normal() {
+        let map = NormalMap::from_height(16, 8, 1.0, |_| 0.0);
+
+        // Cada texel codifica la normal sin perturbar (0, 0, 1)
+        for v in [0.1, 0.5, 0.9] {
+            let texel = map.texture.sample(Vec2::new(0.3, v));
+            assert!((Vec3::new(texel.r, texel.g, texel.b) - Vec3::new(0.5, 0.5, 1.0)).magnitude() < 1e-6);
+        }
+
+        // Aplicado sobre cualquier superficie, la normal queda igual
+        let mut fragment = Fragment::new(0.0, 0.0, 0.0);
+        fragment.normal = Vec3::new(0.0, 1.0, 0.0);
+        fragment.tangent = Vec4::new(1.0, 0.0, 0.0, -1.0);
+        fragment.tex_coords = Vec2::new(0.7, 0.4);
+        assert!((map.perturb(&fragment) - fragment.normal).magnitude() < 1e-6);
+    }
+}
@@ -0,0 +1,118 @@
+use nalgebra_glm::{dot, Vec2, Vec3};
+use std::f32::consts::PI;
+
+use crate::color::LinearColor;
+use crate::fragment::Fragment;
+use crate::noise::smoothstep;
+use crate::mesh::Mesh;
+use crate::shaders::FragmentShader;
+use crate::shadows::sun_visibility;
+use crate::vertex::Vertex;
+use crate::Uniforms;
+
+// Sistema de anillos de un planeta (estilo Saturno/Urano). La malla es un anillo plano
+// generado en código, en el plano XZ del objeto (el ecuador: el planeta gira sobre Y),
+// con radios en unidades del radio del planeta. Se inclina junto con el planeta (Planet::tilt).
+pub struct Ring {
+    pub mesh: Mesh,
+    pub shader: RingShader,
+}
+
+impl Ring {
+    pub fn new(inner_radius: f32, outer_radius: f32, shader: RingShader) -> Self {
+        Ring {
+            mesh: annulus_mesh(inner_radius, outer_radius, 128),
+            shader,
+        }
+    }
+}
+
+// Malla de un anillo con `segments` divisiones alrededor: un vértice en el borde interior
+// y otro en el exterior por cada división (la última repite la primera con otra UV).
+// tex_coords.x va de 0 (borde interior) a 1 (borde exterior) y lo usa el shader
+// para el perfil radial; tex_coords.y es la fracción de vuelta.
+pub fn annulus_mesh(inner_radius: f32, outer_radius: f32, segments: usize) -> Mesh {
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let point = |i: usize, radial: f32| {
+        let angle = 2.0 * PI * i as f32 / segments as f32;
+        let radius = inner_radius + (outer_radius - inner_radius) * radial;
+        let position = Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin());
+        Vertex::new(position, normal, Vec2::new(radial, i as f32 / segments as f32))
+    };
+
+    let mut vertices = Vec::with_capacity((segments + 1) * 2);
+    for i in 0..=segments {
+        vertices.push(point(i, 0.0));
+        vertices.push(point(i, 1.0));
+    }
+
+    let mut indices = Vec::with_capacity(segments * 6);
+    for i in 0..segments as u32 {
+        let (inner_a, outer_a) = (2 * i, 2 * i + 1);
+        let (inner_b, outer_b) = (2 * i + 2, 2 * i + 3);
+
+        indices.extend([inner_a, outer_a, outer_b]);
+        indices.extend([inner_a, outer_b, inner_b]);
+    }
+    Mesh::new(vertices, indices)
+}
+
+// Anillos transparentes con bandas de densidad y divisiones vacías. Se iluminan por
+// ambas caras y quedan a la sombra del planeta con el mismo cálculo de los eclipses.
+pub struct RingShader {
+    pub inner_color: LinearColor,
+    pub outer_color: LinearColor,
+    pub opacity: f32,          // opacidad máxima de las bandas más densas
+    pub gaps: Vec<(f32, f32)>, // divisiones (posición radial 0..1, ancho)
+}
+
+impl RingShader {
+    // Densidad de partículas según la posición radial (0 = borde interior, 1 = exterior)
+    fn density(&self, radial: f32) -> f32 {
+        // Bandas finas superpuestas a distintas frecuencias
+        let bands = 0.55
+            + 0.2 * (radial * 97.0).sin()
+            + 0.15 * (radial * 41.0 + 1.3).sin()
+            + 0.1 * (radial * 13.0 + 0.7).sin();
+
+        // Divisiones: casi vacías en el centro, con bordes suaves
+        let gaps = self.gaps.iter().fold(1.0, |density, &(center, width)| {
+            let distance = ((radial - center).abs() / (width * 0.5)).min(1.0);
+            density * (0.05 + 0.95 * distance * distance)
+        });
+
+        // Desvanecer en los bordes interior y exterior
+        let edges = smoothstep(0.0, 0.05, radial) * (1.0 - smoothstep(0.9, 1.0, radial));
+
+        (bands * gaps * edges).clamp(0.0, 1.0)
+    }
+}
+
+impl FragmentShader for RingShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let radial = fragment.tex_coords.x.clamp(0.0, 1.0);
+        let density = self.density(radial);
+
+        let to_light = (uniforms.sun_position - fragment.world_position).normalize();
+
+        // Ambas caras reciben luz: el anillo es una capa fina de partículas
+        let n_dot_l = dot(&fragment.normal, &to_light).abs();
+
+        // Sombra del planeta (y de cualquier otro cuerpo) sobre el anillo
+        let visibility = if uniforms.shadows {
+            sun_visibility(
+                &fragment.world_position,
+                &uniforms.sun_position,
+                uniforms.sun_radius,
+                uniforms.occluders,
+            )
+        } else {
+            1.0
+        };
+
+        let albedo = self.inner_color.lerp(&self.outer_color, radial) * (0.7 + 0.3 * density);
+        let light = 0.04 + (0.3 + 0.7 * n_dot_l) * visibility;
+
+        (albedo * light).with_alpha(self.opacity * density)
+    }
+}
@@ -0,0 +1,374 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+use crate::color::LinearColor;
+use crate::mesh::Mesh;
+use crate::obj::Obj;
+use crate::atmosphere::Atmosphere;
+use crate::clouds::{CloudLayer, CloudMap, CloudNoise, CloudShader};
+use crate::emission::{CityLights, EmissionMap, NightLights};
+use crate::planet::Planet;
+use crate::relief::{craters, ridges, NormalMap};
+use crate::ring::{Ring, RingShader};
+use crate::spaceship::{ExhaustLayer, Spaceship};
+use crate::sphere::SphereLods;
+use crate::starfield::Starfield;
+use crate::texture::{FilterMode, Texture};
+use crate::config::Config;
+use crate::continents::Continents;
+use crate::vertex::Vertex;
+use crate::rasterizer::BlendMode;
+use crate::shaders::{Albedo, GlowShader, SpecularMask, SurfaceShader};
+use crate::material::Material;
+use crate::gaseous_shader::{GaseousShader, RockyShader, SunShader};
+
+const ORBIT_SEGMENTS: usize = 120;
+
+// Brillo del sol en HDR: por encima de 1.0 para que el tone mapping conserve el
+// degradado de la granulación en lugar de recortarlo a amarillo plano
+const SUN_INTENSITY: f32 = 1.6;
+
+// Resolución de los mapas de normales generados y exageración de las alturas
+const RELIEF_WIDTH: usize = 1024;
+const RELIEF_HEIGHT: usize = 512;
+const RELIEF_EXAGGERATION: f32 = 1.5;
+
+// Radio de las esferas generadas en espacio de objeto; las escalas de los cuerpos
+// están pensadas para este tamaño (el de la sphere.obj que se usaba antes)
+const SPHERE_RADIUS: f32 = 1.645;
+
+// Semilla del catálogo de estrellas: el mismo cielo en cada ejecución
+const STARFIELD_SEED: u64 = 0x5EED_57A2;
+
+// Estado completo del sistema solar, independiente de la ventana
+pub struct Scene {
+    pub sun: Planet,
+    pub planets: Vec<Planet>,
+    pub orbits: Vec<Orbit>,
+    pub spheres: SphereLods,
+    pub sphere_radius: f32,  // radio de las esferas en espacio de objeto
+    pub spaceship: Spaceship,
+    pub spaceship_mesh: Mesh,
+    pub spaceship_shader: SurfaceShader,
+    pub spaceship_exhaust: Vec<ExhaustLayer>,
+    pub starfield: Starfield,
+}
+
+// Órbita de un planeta como polilínea cerrada, con su centro y radio para
+// atenuar la mitad más lejana de la cámara
+pub struct Orbit {
+    pub vertices: Vec<Vertex>,
+    pub center: Vec3,
+    pub radius: f32,
+    pub color: LinearColor,
+}
+
+impl Scene {
+    pub fn new(config: &Config) -> Self {
+        // Esferas generadas en código, con todos sus niveles de detalle
+        let spheres = SphereLods::new(config.sphere_mesh, SPHERE_RADIUS);
+
+        // Cargar nave espacial
+        let spaceship_obj = Obj::load("assets/models/spaceship.obj").expect("Failed to load spaceship");
+        let spaceship_mesh = spaceship_obj.get_mesh();
+
+        println!(
+            "Nave cargada con {} vértices y {} triángulos",
+            spaceship_mesh.vertices.len(),
+            spaceship_mesh.triangle_count()
+        );
+
+        // Configuración del sistema solar - centrado en el origen
+        let center = Vec3::new(0.0, 0.0, 0.0);
+
+        // SOL
+        let sun = Planet {
+            translation: center,
+            rotation: Vec3::new(0.0, 0.0, 0.0),
+            tilt: 0.0,
+            scale: 80.0,
+            orbit_speed: 0.0,
+            rotation_speed: 0.0,
+            orbit_radius: 0.0,
+            orbit_angle: 0.0,
+            center_x: center.x,
+            center_y: center.y,
+            atmosphere: None,
+            clouds: None,
+            rings: None,
+            orbit_color: LinearColor::black(),  // sin órbita (radio 0)
+            shader: Box::new(SunShader { material: Material::emissive(LinearColor::from_hex(0xFFDD00) * SUN_INTENSITY) }),
+        };
+
+        // Texturas equirectangulares opcionales: si el archivo no existe el planeta
+        // conserva su color plano
+        let albedo = |hex: u32, texture_path: Option<&str>| {
+            match texture_path.and_then(|path| load_texture(path, config.texture_filter)) {
+                Some(texture) => Albedo::Texture(texture),
+                None => Albedo::Color(LinearColor::from_hex(hex)),
+            }
+        };
+
+        // Los cuerpos rocosos y gaseosos son mates; el agua de la Tierra tiene brillo especular
+        // (los continentes no: ver SpecularMask)
+        let matte = Material::default();
+        let ocean = Material {
+            specular: LinearColor::from_hex(0x707070),
+            shininess: 48.0,
+            ..Material::default()
+        };
+
+        // Relieve de los cuerpos rocosos: un mapa de normales desde archivo si existe; si no,
+        // se genera desde las alturas procedurales
+        let relief = |path: &str, height_fn: fn(Vec3) -> f32| {
+            match NormalMap::load(path) {
+                Ok(map) => {
+                    println!("Mapa de normales cargado: {}", path);
+                    map
+                }
+                Err(_) => NormalMap::from_height(RELIEF_WIDTH, RELIEF_HEIGHT, RELIEF_EXAGGERATION, height_fn),
+            }
+        };
+
+        // Tierra: con el mapa del día usa los mapas de luces y de brillo si existen (sin
+        // mapa de luces no hay ciudades; sin mapa de brillo, toda la superficie brilla).
+        // Sin texturas, el color de día, las ciudades y el brillo del agua salen de los
+        // mismos continentes procedurales, así las luces no caen en medio del océano
+        let continents = Continents { land: 0.3 };
+        let (earth_albedo, earth_lights, earth_specular) = match load_texture("assets/textures/earth.jpg", config.texture_filter) {
+            Some(texture) => (
+                Albedo::Texture(texture),
+                load_texture("assets/textures/earth_night.jpg", config.texture_filter).map(EmissionMap::Texture),
+                match Texture::load_data("assets/textures/earth_specular.png") {
+                    Ok(mut map) => {
+                        map.filter = config.texture_filter;
+                        println!("Mapa de brillo cargado: assets/textures/earth_specular.png");
+                        SpecularMask::Map(map)
+                    }
+                    Err(_) => SpecularMask::Uniform,
+                },
+            ),
+            None => (
+                Albedo::Continents {
+                    ocean: LinearColor::from_hex(0x4169E1),
+                    land: LinearColor::from_hex(0x5B7A3A),
+                    continents,
+                },
+                Some(EmissionMap::Cities(CityLights {
+                    color: LinearColor::new(1.0, 0.72, 0.4),
+                    continents,
+                })),
+                SpecularMask::Ocean(continents),
+            ),
+        };
+
+        // Nubes de la Tierra: mapa de cobertura si existe; si no, nubes procedurales
+        let earth_clouds = match Texture::load_data("assets/textures/earth_clouds.png") {
+            Ok(mut texture) => {
+                texture.filter = config.texture_filter;
+                println!("Mapa de nubes cargado: assets/textures/earth_clouds.png");
+                CloudMap::Texture(texture)
+            }
+            Err(_) => CloudMap::Noise(CloudNoise {
+                coverage: 0.5,
+                frequency: 4.0,
+                evolution: 0.05,
+            }),
+        };
+
+        // PLANETAS
+        let mut planets = vec![
+            // Mercurio - Rocoso pequeño, gris marrón, cubierto de cráteres
+            Planet::new(180.0, 15.0, 0.04, 0.03, 0.0,
+                Box::new(RockyShader {
+                    albedo: albedo(0x8C7853, None),
+                    relief: Some(relief("assets/textures/mercury_normal.png", mercury_height)),
+                    material: matte,
+                }))
+                .with_orbit_color(LinearColor::from_hex(0x8C7853)),
+            // Venus - Rocoso, amarillo, con una neblina anaranjada densa
+            Planet::new(240.0, 25.0, 0.03, 0.025, PI / 4.0,
+                Box::new(RockyShader { albedo: albedo(0xFFC649, None), relief: None, material: matte }))
+                .with_orbit_color(LinearColor::from_hex(0xC9A445))
+                .with_atmosphere(Atmosphere::new(
+                    1.05,
+                    LinearColor::new(1.0, 0.65, 0.3),
+                    LinearColor::new(1.0, 0.8, 0.5),
+                    0.08,
+                )),
+            // Tierra - Normal con agua, azul, con atmósfera azul
+            Planet::new(310.0, 28.0, 0.025, 0.02, PI / 2.0,
+                Box::new(SurfaceShader {
+                    albedo: earth_albedo,
+                    night_lights: earth_lights.map(|map| NightLights {
+                        map,
+                        intensity: 0.8,
+                        twilight: 0.15,
+                    }),
+                    material: ocean,
+                    specular_mask: earth_specular,
+                }))
+                .with_orbit_color(LinearColor::from_hex(0x4169E1))
+                .with_clouds(CloudLayer::new(
+                    1.02,
+                    0.026,  // un poco más rápido que la superficie
+                    CloudShader {
+                        map: earth_clouds,
+                        color: LinearColor::new(1.0, 1.0, 1.0),
+                        opacity: 0.9,
+                    },
+                ))
+                .with_atmosphere(Atmosphere::new(
+                    1.06,
+                    LinearColor::new(0.3, 0.55, 1.0),
+                    LinearColor::new(1.0, 0.95, 0.9),
+                    0.07,
+                )),
+            // Marte - Rocoso rojo, con cordilleras, cráteres y una neblina anaranjada fina
+            Planet::new(380.0, 22.0, 0.02, 0.018, 3.0 * PI / 4.0,
+                Box::new(RockyShader {
+                    albedo: albedo(0xCD5C5C, Some("assets/textures/mars.jpg")),
+                    relief: Some(relief("assets/textures/mars_normal.png", mars_height)),
+                    material: matte,
+                }))
+                .with_orbit_color(LinearColor::from_hex(0xCD5C5C))
+                .with_atmosphere(Atmosphere::new(
+                    1.03,
+                    LinearColor::new(1.0, 0.55, 0.3),
+                    LinearColor::new(1.0, 0.75, 0.55),
+                    0.04,
+                )),
+            // Júpiter - Gaseoso grande, dorado/naranja
+            Planet::new(510.0, 55.0, 0.01, 0.04, PI,
+                Box::new(GaseousShader { albedo: albedo(0xDAA520, None), material: matte }))
+                .with_orbit_color(LinearColor::from_hex(0xDAA520)),
+            // Saturno - Gaseoso color arena, con anillos inclinados
+            Planet::new(590.0, 40.0, 0.007, 0.038, 0.0,
+                Box::new(GaseousShader { albedo: albedo(0xE3C588, None), material: matte }))
+                .with_orbit_color(LinearColor::from_hex(0xE3C588))
+                .with_tilt(0.47)  // ~27°
+                .with_rings(Ring::new(
+                    1.25,
+                    2.3,
+                    RingShader {
+                        inner_color: LinearColor::from_hex(0x8A7A60),
+                        outer_color: LinearColor::from_hex(0xE0D0B0),
+                        opacity: 0.9,
+                        gaps: vec![(0.62, 0.06), (0.9, 0.015)],  // división de Cassini y de Encke
+                    },
+                )),
+        ];
+
+        // Configurar profundidad Z para cada planeta
+        for (i, planet) in planets.iter_mut().enumerate() {
+            planet.translation.z = (i as f32 - 2.0) * 50.0;
+        }
+
+        // Para cada planeta (que no sea el sol, radio 0) creamos un anillo de vértices,
+        // con un tono apagado de su color de órbita
+        let mut orbits: Vec<Orbit> = Vec::new();
+
+        for planet in &planets {
+            // Ignorar "órbita" del sol (radio 0)
+            if planet.orbit_radius <= 0.0 {
+                continue;
+            }
+
+            let center = Vec3::new(planet.center_x, planet.center_y, planet.translation.z);
+            let mut vertices: Vec<Vertex> = Vec::with_capacity(ORBIT_SEGMENTS);
+
+            for i in 0..ORBIT_SEGMENTS {
+                let angle = 2.0 * PI * (i as f32) / (ORBIT_SEGMENTS as f32);
+                let x = center.x + planet.orbit_radius * angle.cos();
+                let y = center.y + planet.orbit_radius * angle.sin();
+
+                vertices.push(Vertex {
+                    position: Vec3::new(x, y, center.z),  // misma profundidad aproximada que el planeta
+                    ..Vertex::default()
+                });
+            }
+
+            orbits.push(Orbit {
+                vertices,
+                center,
+                radius: planet.orbit_radius,
+                color: (planet.orbit_color * 0.35).with_alpha(0.8),
+            });
+        }
+
+        Scene {
+            sun,
+            planets,
+            orbits,
+            spheres,
+            sphere_radius: SPHERE_RADIUS,
+            spaceship: Spaceship::new(),
+            spaceship_mesh,
+            // Gris metálico con brillo especular
+            spaceship_shader: SurfaceShader {
+                albedo: Albedo::Color(LinearColor::from_hex(0xCCCCCC)),
+                night_lights: None,
+                material: Material {
+                    specular: LinearColor::from_hex(0xFFFFFF),
+                    shininess: 32.0,
+                    ..Material::default()
+                },
+                specular_mask: SpecularMask::Uniform,
+            },
+            // Escape del motor: una pluma azul translúcida y un núcleo caliente que suma luz
+            spaceship_exhaust: vec![
+                ExhaustLayer {
+                    radius: 0.35,
+                    length: 3.0,
+                    blend_mode: BlendMode::Alpha,
+                    shader: GlowShader {
+                        color: LinearColor::from_hex(0x3366FF).with_alpha(0.12),
+                        falloff: 2.0,
+                    },
+                },
+                ExhaustLayer {
+                    radius: 0.18,
+                    length: 1.2,
+                    blend_mode: BlendMode::Additive,
+                    shader: GlowShader {
+                        color: LinearColor::new(0.6, 0.8, 1.0) * 3.0,
+                        falloff: 2.0,
+                    },
+                },
+            ],
+            starfield: Starfield::new(STARFIELD_SEED, config.milky_way),
+        }
+    }
+
+    // Avanzar la simulación un frame
+    pub fn update(&mut self) {
+        for planet in &mut self.planets {
+            planet.update();
+        }
+        self.sun.rotation.y += self.sun.rotation_speed;
+    }
+}
+
+// Alturas de Mercurio (en radios): cráteres de varios tamaños y escarpes largos
+fn mercury_height(p: Vec3) -> f32 {
+    craters(p, 4.0) + craters(p, 10.0) + craters(p, 24.0) + 0.004 * ridges(p * 5.0, 3)
+}
+
+// Alturas de Marte: cordilleras y cráteres más escasos
+fn mars_height(p: Vec3) -> f32 {
+    0.03 * ridges(p * 2.5, 5) + 0.6 * craters(p, 6.0)
+}
+
+fn load_texture(path: &str, filter: FilterMode) -> Option<Texture> {
+    match Texture::load(path) {
+        Ok(mut texture) => {
+            texture.filter = filter;
+            println!("Textura cargada: {} ({}x{})", path, texture.width, texture.height);
+            Some(texture)
+        }
+        Err(e) => {
+            println!("No se pudo cargar {} ({}), usando color plano", path, e);
+            None
+        }
+    }
+}
@@ -1,7 +1,106 @@
-use nalgebra_glm::{Vec3, Vec4, Mat3};
+use nalgebra_glm::{Vec3, Vec4, Mat3, Mat4};
+use crate::color::LinearColor;
+use crate::continents::Continents;
+use crate::emission::NightLights;
+use crate::fragment::Fragment;
+use crate::material::Material;
+use crate::texture::Texture;
 use crate::vertex::Vertex;
 use crate::Uniforms;
 
+// Shader de fragmentos de un material. Cada tipo de superficie es un struct con sus
+// propios parámetros; los datos del frame (matrices, tiempo, cámara, sol) llegan en
+// `uniforms`. Se ejecuta en paralelo desde los hilos del rasterizador.
+pub trait FragmentShader: Send + Sync {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor;
+}
+
+// Color base de un material: plano, muestreado de una textura o con continentes procedurales
+pub enum Albedo {
+    Color(LinearColor),
+    Texture(Texture),
+    Continents {
+        ocean: LinearColor,
+        land: LinearColor,
+        continents: Continents,
+    },
+}
+
+impl Albedo {
+    pub fn sample(&self, fragment: &Fragment) -> LinearColor {
+        match self {
+            Albedo::Color(color) => *color,
+            Albedo::Texture(texture) => texture.sample(fragment.tex_coords),
+            Albedo::Continents { ocean, land, continents } => {
+                ocean.lerp(land, continents.mask(fragment.object_position.normalize()))
+            }
+        }
+    }
+}
+
+// Qué parte de la superficie tiene el brillo especular del material
+pub enum SpecularMask {
+    Uniform,             // toda la superficie (metal de la nave)
+    Ocean(Continents),   // solo el agua de los continentes procedurales
+    Map(Texture),        // mapa de brillo equirectangular (blanco = agua, negro = tierra)
+}
+
+impl SpecularMask {
+    // Peso del especular (0..1) en un punto de la superficie
+    fn weight(&self, fragment: &Fragment) -> f32 {
+        match self {
+            SpecularMask::Uniform => 1.0,
+            SpecularMask::Ocean(continents) => 1.0 - continents.mask(fragment.object_position.normalize()),
+            SpecularMask::Map(map) => map.sample(fragment.tex_coords).r.clamp(0.0, 1.0),
+        }
+    }
+}
+
+// Superficie iluminada sin patrón procedural (Tierra, nave espacial)
+pub struct SurfaceShader {
+    pub albedo: Albedo,
+    pub night_lights: Option<NightLights>,  // luces de ciudades en el lado nocturno
+    pub material: Material,
+    pub specular_mask: SpecularMask,
+}
+
+impl FragmentShader for SurfaceShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let albedo = self.albedo.sample(fragment);
+        let material = Material {
+            specular: self.material.specular * self.specular_mask.weight(fragment),
+            ..self.material
+        };
+
+        let color = material.shade(albedo, fragment, uniforms);
+        match &self.night_lights {
+            Some(lights) => color + lights.emission(fragment, uniforms),
+            None => color,
+        }
+    }
+}
+
+// Brillo translúcido (escape de motores, halos): más opaco donde la superficie mira a
+// la cámara y transparente hacia el borde, así la capa no muestra una silueta dura.
+// La opacidad máxima es `color.a`; el modo de mezcla lo decide el draw.
+pub struct GlowShader {
+    pub color: LinearColor,
+    pub falloff: f32,  // exponente: más alto = brillo más concentrado en el centro
+}
+
+impl FragmentShader for GlowShader {
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> LinearColor {
+        let to_camera = (uniforms.camera_position - fragment.world_position).normalize();
+        let facing = fragment.normal.dot(&to_camera).abs();
+
+        // Parpadeo rápido, como una llama
+        let flicker = 0.9 + 0.1 * (uniforms.time * 37.0).sin();
+
+        let alpha = self.color.a * facing.powf(self.falloff) * flicker;
+        self.color.with_alpha(alpha.clamp(0.0, 1.0))
+    }
+}
+
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     let position = Vec4::new(
         vertex.position.x,
@@ -19,43 +118,143 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     );
     
     // Model -> World -> View -> Clip (para renderizado)
+    // La división de perspectiva se hace después del clipping (ver project_to_screen)
     let clip_space = uniforms.projection_matrix 
         * uniforms.view_matrix 
-        * uniforms.model_matrix 
-        * position;
+        * world_position_4;
+
+    // Las tangentes son direcciones sobre la superficie y se transforman con la parte
+    // lineal del modelo; las normales usan la matriz de normales de los uniforms
+    let model_mat3: Mat3 = uniforms.model_matrix.fixed_view::<3, 3>(0, 0).into_owned();
+
+    let transformed_normal = (uniforms.normal_matrix * vertex.normal).normalize();
+    let tangent = (model_mat3 * vertex.tangent.xyz()).normalize();
+
+    Vertex {
+        position: world_position,  // IMPORTANTE: Guardamos la posición MUNDIAL, no la original
+        normal: vertex.normal,
+        tex_coords: vertex.tex_coords,
+        tangent: vertex.tangent,
+        color: vertex.color,
+        transformed_position: vertex.transformed_position,
+        transformed_normal,
+        transformed_tangent: Vec4::new(tangent.x, tangent.y, tangent.z, vertex.tangent.w),
+        clip_position: clip_space,
+        inv_w: 1.0 / clip_space.w,
+        object_position: vertex.position,  // para patrones procedurales fijos a la superficie
+    }
+}
+
+// Inversa transpuesta de la parte lineal del modelo: mantiene las normales perpendiculares
+// a la superficie cuando la escala no es uniforme (escape de la nave)
+pub fn normal_matrix(model_matrix: &Mat4) -> Mat3 {
+    let model_mat3: Mat3 = model_matrix.fixed_view::<3, 3>(0, 0).into_owned();
+    model_mat3
+        .try_inverse()
+        .map_or(model_mat3, |inverse| inverse.transpose())
+}
 
-    // Perspective division: Clip -> NDC
+// Perspective division + viewport: Clip -> NDC -> Screen space.
+// Solo debe llamarse con vértices ya recortados contra el frustum (w > 0).
+// Guarda 1/w para la interpolación con corrección de perspectiva en triangle().
+pub fn project_to_screen(vertex: &mut Vertex, viewport_matrix: &Mat4) {
+    let clip_space = vertex.clip_position;
     let w = clip_space.w;
+    vertex.inv_w = 1.0 / w;
     let ndc = Vec3::new(
         clip_space.x / w,
         clip_space.y / w,
         clip_space.z / w
     );
-    
-    // NDC -> Screen space
-    let screen = uniforms.viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
-    
-    let transformed_position = Vec3::new(
+
+    let screen = *viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+
+    vertex.transformed_position = Vec3::new(
         screen.x,
         screen.y,
         screen.z
     );
+}
 
-    // Transform normal (en espacio mundial)
-    let model_mat3 = Mat3::new(
-        uniforms.model_matrix[0], uniforms.model_matrix[1], uniforms.model_matrix[2],
-        uniforms.model_matrix[4], uniforms.model_matrix[5], uniforms.model_matrix[6],
-        uniforms.model_matrix[8], uniforms.model_matrix[9], uniforms.model_matrix[10]
-    );
-    
-    let transformed_normal = (model_mat3 * vertex.normal).normalize();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::FilterMode;
+    use nalgebra_glm::{self as glm, Vec2};
+    use std::f32::consts::PI;
 
-    Vertex {
-        position: world_position,  // IMPORTANTE: Guardamos la posición MUNDIAL, no la original
-        normal: vertex.normal,
-        tex_coords: vertex.tex_coords,
-        color: vertex.color,
-        transformed_position,
-        transformed_normal,
+    fn uniforms(model_matrix: Mat4) -> Uniforms<'static> {
+        Uniforms {
+            model_matrix,
+            normal_matrix: normal_matrix(&model_matrix),
+            view_matrix: Mat4::identity(),
+            projection_matrix: Mat4::identity(),
+            viewport_matrix: Mat4::identity(),
+            camera_position: Vec3::zeros(),
+            sun_position: Vec3::zeros(),
+            sun_radius: 1.0,
+            occluders: &[],
+            clouds: None,
+            shadows: false,
+            time: 0.0,
+        }
+    }
+
+    fn transformed_normal(model_matrix: Mat4, normal: Vec3) -> Vec3 {
+        let vertex = Vertex::new(Vec3::zeros(), normal, Vec2::zeros());
+        vertex_shader(&vertex, &uniforms(model_matrix)).transformed_normal
+    }
+
+    #[test]
+    fn normals_follow_the_model_rotation() {
+        let rotation = glm::rotate_y(&Mat4::identity(), 0.5);
+        let expected = (rotation * Vec4::new(1.0, 0.0, 0.0, 0.0)).xyz();
+
+        let normal = transformed_normal(rotation, Vec3::new(1.0, 0.0, 0.0));
+        assert!((normal - expected).magnitude() < 1e-5, "{:?} != {:?}", normal, expected);
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        // El plano x + y = 1 estirado 4 veces en Y queda x + y / 4 = 1
+        let stretch = glm::scale(&Mat4::identity(), &Vec3::new(1.0, 4.0, 1.0));
+        let expected = Vec3::new(1.0, 0.25, 0.0).normalize();
+
+        let normal = transformed_normal(stretch, Vec3::new(1.0, 1.0, 0.0).normalize());
+        assert!((normal - expected).magnitude() < 1e-5, "{:?} != {:?}", normal, expected);
+    }
+
+    #[test]
+    fn procedural_land_gets_no_specular() {
+        let continents = Continents { land: 0.3 };
+        let mask = SpecularMask::Ocean(continents);
+
+        // Recorrer la esfera hasta encontrar un punto en tierra firme y otro en el océano
+        let directions: Vec<Vec3> = (1..16)
+            .flat_map(|lat| (0..32).map(move |lon| (lat as f32 * PI / 16.0, lon as f32 * PI / 16.0)))
+            .map(|(theta, phi)| Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()))
+            .collect();
+        let land = directions.iter().find(|p| continents.mask(**p) >= 1.0).expect("hay continentes");
+        let ocean = directions.iter().find(|p| continents.mask(**p) <= 0.0).expect("hay océano");
+
+        let mut fragment = Fragment::new(0.0, 0.0, 0.0);
+        fragment.object_position = *land * 2.0;
+        assert_eq!(mask.weight(&fragment), 0.0);
+        fragment.object_position = *ocean * 2.0;
+        assert_eq!(mask.weight(&fragment), 1.0);
+    }
+
+    #[test]
+    fn specular_map_texel_on_land_gets_no_specular() {
+        // Mitad izquierda agua (blanco), mitad derecha tierra (negro)
+        let mut map = Texture::from_pixels(2, 1, vec![LinearColor::new(1.0, 1.0, 1.0), LinearColor::black()]);
+        map.filter = FilterMode::Nearest;
+        let mask = SpecularMask::Map(map);
+
+        let mut fragment = Fragment::new(0.0, 0.0, 0.0);
+        fragment.tex_coords = Vec2::new(0.75, 0.5);
+        assert_eq!(mask.weight(&fragment), 0.0);
+        fragment.tex_coords = Vec2::new(0.25, 0.5);
+        assert_eq!(mask.weight(&fragment), 1.0);
     }
-}
\ No newline at end of file
+}
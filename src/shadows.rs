@@ -0,0 +1,119 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// Esfera que puede proyectar sombra sobre otros cuerpos (planetas)
+#[derive(Clone, Copy, Debug)]
+pub struct Occluder {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+// Fracción visible del disco solar desde `point` (1 = totalmente iluminado, 0 = umbra).
+// Cada esfera se ve como un disco en el cielo del punto; el área que se superpone con
+// el disco del sol da la penumbra de eclipses y tránsitos.
+pub fn sun_visibility(point: &Vec3, sun_center: &Vec3, sun_radius: f32, occluders: &[Occluder]) -> f32 {
+    let to_sun = sun_center - point;
+    let sun_distance = to_sun.magnitude();
+    if sun_distance <= sun_radius {
+        return 1.0;
+    }
+
+    let sun_direction = to_sun / sun_distance;
+    let sun_angle = (sun_radius / sun_distance).asin();
+    let sun_area = PI * sun_angle * sun_angle;
+
+    let mut visibility = 1.0;
+
+    for occluder in occluders {
+        let to_occluder = occluder.center - point;
+        let occluder_distance = to_occluder.magnitude();
+
+        // El punto está sobre esta esfera: un cuerpo no se eclipsa a sí mismo
+        // (el lado nocturno ya lo resuelve el término difuso)
+        if occluder_distance <= occluder.radius * 1.001 {
+            continue;
+        }
+
+        // Solo tapan la luz las esferas que están entre el punto y el sol
+        let along = to_occluder.dot(&sun_direction);
+        if along <= 0.0 || occluder_distance - occluder.radius > sun_distance {
+            continue;
+        }
+
+        let occluder_angle = (occluder.radius / occluder_distance).asin();
+        let separation = (along / occluder_distance).clamp(-1.0, 1.0).acos();
+
+        visibility -= disc_overlap(sun_angle, occluder_angle, separation) / sun_area;
+    }
+
+    visibility.max(0.0)
+}
+
+// Área de la intersección de dos discos de radios r1 y r2 con centros a distancia d
+fn disc_overlap(r1: f32, r2: f32, d: f32) -> f32 {
+    if d >= r1 + r2 {
+        return 0.0;
+    }
+    if d <= (r1 - r2).abs() {
+        let r = r1.min(r2);
+        return PI * r * r;
+    }
+
+    let a1 = r1 * r1 * ((d * d + r1 * r1 - r2 * r2) / (2.0 * d * r1)).clamp(-1.0, 1.0).acos();
+    let a2 = r2 * r2 * ((d * d + r2 * r2 - r1 * r1) / (2.0 * d * r2)).clamp(-1.0, 1.0).acos();
+    let kite = 0.5 * ((-d + r1 + r2) * (d + r1 - r2) * (d - r1 + r2) * (d + r1 + r2)).max(0.0).sqrt();
+
+    a1 + a2 - kite
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUN: Vec3 = Vec3::new(0.0, 0.0, 1000.0);
+    const SUN_RADIUS: f32 = 100.0;
+
+    // Occluder a distancia 100 del origen, con el mismo radio angular que el sol
+    // (asin 0.1) y desviado `angle` radianes de la dirección al sol
+    fn occluder_at(angle: f32) -> Occluder {
+        Occluder {
+            center: Vec3::new(angle.sin(), 0.0, angle.cos()) * 100.0,
+            radius: 10.0,
+        }
+    }
+
+    #[test]
+    fn separate_discs_do_not_overlap() {
+        assert_eq!(disc_overlap(1.0, 1.0, 2.5), 0.0);
+
+        let occluders = [occluder_at(0.5)];
+        assert_eq!(sun_visibility(&Vec3::zeros(), &SUN, SUN_RADIUS, &occluders), 1.0);
+    }
+
+    #[test]
+    fn larger_centered_occluder_covers_the_sun() {
+        assert!((disc_overlap(1.0, 2.0, 0.5) - PI).abs() < 1e-5);
+
+        let occluders = [Occluder { center: Vec3::new(0.0, 0.0, 100.0), radius: 20.0 }];
+        assert_eq!(sun_visibility(&Vec3::zeros(), &SUN, SUN_RADIUS, &occluders), 0.0);
+    }
+
+    #[test]
+    fn partial_overlap_matches_lens_area() {
+        // Dos discos unitarios con centros a distancia 1: 2π/3 - √3/2
+        let lens = 2.0 * PI / 3.0 - 3.0f32.sqrt() / 2.0;
+        assert!((disc_overlap(1.0, 1.0, 1.0) - lens).abs() < 1e-5);
+
+        // Mismo caso en el cielo: discos iguales separados por su radio angular
+        let radius = 0.1f32.asin();
+        let occluders = [occluder_at(radius)];
+        let visibility = sun_visibility(&Vec3::zeros(), &SUN, SUN_RADIUS, &occluders);
+        assert!((visibility - (1.0 - lens / PI)).abs() < 1e-2, "visibilidad {}", visibility);
+    }
+
+    #[test]
+    fn occluder_behind_the_receiver_casts_no_shadow() {
+        let occluders = [Occluder { center: Vec3::new(0.0, 0.0, -100.0), radius: 20.0 }];
+        assert_eq!(sun_visibility(&Vec3::zeros(), &SUN, SUN_RADIUS, &occluders), 1.0);
+    }
+}
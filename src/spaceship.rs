@@ -1,5 +1,7 @@
-use nalgebra_glm::Vec3;
+use nalgebra_glm::{self as glm, Mat4, Vec3};
 use crate::camera::Camera;
+use crate::rasterizer::BlendMode;
+use crate::shaders::GlowShader;
 
 pub struct Spaceship {
     pub offset: Vec3,  // Offset relativo a la cámara
@@ -62,4 +64,24 @@ impl Spaceship {
         println!("Nave offset: x={:.1}, y={:.1}, z={:.1}", 
                  self.offset.x, self.offset.y, self.offset.z);
     }
-}
\ No newline at end of file
+}
+
+// Capa transparente del escape del motor: la esfera base estirada en un elipsoide
+// que sale de la tobera hacia atrás
+pub struct ExhaustLayer {
+    pub radius: f32,   // en unidades del modelo de la nave
+    pub length: f32,
+    pub blend_mode: BlendMode,
+    pub shader: GlowShader,
+}
+
+impl ExhaustLayer {
+    // Matriz de modelo relativa a la de la nave. La tobera de spaceship.obj está en el
+    // centro de la cola; +Z del modelo apunta hacia atrás.
+    pub fn model_matrix(&self, ship_model_matrix: &Mat4, sphere_radius: f32) -> Mat4 {
+        let nozzle = Vec3::new(0.0, 0.38, 2.07);
+        let center = nozzle + Vec3::new(0.0, 0.0, self.length * 0.5);
+        let size = Vec3::new(self.radius, self.radius, self.length * 0.5) / sphere_radius;
+        glm::scale(&glm::translate(ship_model_matrix, &center), &size)
+    }
+}
@@ -0,0 +1,339 @@
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use crate::mesh::{Bounds, Mesh};
+use crate::vertex::Vertex;
+
+// Cantidad de niveles de detalle y error máximo permitido en la silueta, en píxeles
+const LOD_LEVELS: u32 = 6;
+const LOD_TOLERANCE: f32 = 0.5;
+
+// Forma de teselar la esfera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SphereMesh {
+    Uv,   // meridianos y paralelos: UV sin distorsión, triángulos apiñados en los polos
+    Ico,  // icosaedro subdividido: triángulos casi iguales en toda la superficie
+}
+
+// Un nivel de detalle: la malla y cuánto se aparta de la esfera real
+struct SphereLod {
+    mesh: Mesh,
+    max_angle: f32,  // mayor ángulo entre el centro de un triángulo y sus vértices
+}
+
+// Esferas del mismo radio con cada vez más triángulos. Cada cuerpo elige un nivel por
+// frame según su radio en pantalla: los lejanos usan pocos triángulos y los cercanos
+// conservan una silueta redonda.
+pub struct SphereLods {
+    levels: Vec<SphereLod>,
+}
+
+impl SphereLods {
+    pub fn new(mesh: SphereMesh, radius: f32) -> Self {
+        let levels = (0..LOD_LEVELS)
+            .map(|level| {
+                let mesh = match mesh {
+                    SphereMesh::Uv => {
+                        let segments = 6 << level;
+                        uv_sphere(radius, segments, segments / 2)
+                    }
+                    SphereMesh::Ico => icosphere(radius, level),
+                };
+                let max_angle = max_angle(&mesh);
+                SphereLod { mesh, max_angle }
+            })
+            .collect();
+
+        SphereLods { levels }
+    }
+
+    // Límites de la esfera, calculados sobre el nivel más fino
+    pub fn bounds(&self) -> &Bounds {
+        &self.levels.last().unwrap().mesh.bounds
+    }
+
+    // Primer nivel cuya silueta se aparta menos de LOD_TOLERANCE píxeles del círculo:
+    // un triángulo que abarca un ángulo α se hunde r (1 - cos α) en su centro
+    pub fn select(&self, screen_radius: f32) -> &Mesh {
+        let level = self.levels
+            .iter()
+            .find(|level| screen_radius * (1.0 - level.max_angle.cos()) <= LOD_TOLERANCE)
+            .unwrap_or_else(|| self.levels.last().unwrap());
+        &level.mesh
+    }
+}
+
+// Esfera de meridianos y paralelos: `segments` divisiones alrededor y `rings` de polo a polo.
+// En los polos cada columna es un solo triángulo.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
+    let mut vertices = Vec::with_capacity((segments * rings * 6) as usize);
+
+    for ring in 0..rings {
+        let v0 = ring as f32 / rings as f32;
+        let v1 = (ring + 1) as f32 / rings as f32;
+
+        for segment in 0..segments {
+            let u0 = segment as f32 / segments as f32;
+            let u1 = (segment + 1) as f32 / segments as f32;
+            let u_mid = (u0 + u1) * 0.5;
+
+            if ring == 0 {
+                push_triangle(&mut vertices, [
+                    surface_vertex(radius, Vec2::new(u_mid, v0)),
+                    surface_vertex(radius, Vec2::new(u0, v1)),
+                    surface_vertex(radius, Vec2::new(u1, v1)),
+                ]);
+            } else if ring == rings - 1 {
+                push_triangle(&mut vertices, [
+                    surface_vertex(radius, Vec2::new(u0, v0)),
+                    surface_vertex(radius, Vec2::new(u1, v0)),
+                    surface_vertex(radius, Vec2::new(u_mid, v1)),
+                ]);
+            } else {
+                let (a, b) = (Vec2::new(u0, v0), Vec2::new(u1, v0));
+                let (c, d) = (Vec2::new(u0, v1), Vec2::new(u1, v1));
+                push_triangle(&mut vertices, [
+                    surface_vertex(radius, a),
+                    surface_vertex(radius, c),
+                    surface_vertex(radius, d),
+                ]);
+                push_triangle(&mut vertices, [
+                    surface_vertex(radius, a),
+                    surface_vertex(radius, d),
+                    surface_vertex(radius, b),
+                ]);
+            }
+        }
+    }
+
+    Mesh::from_triangles(vertices)
+}
+
+// Icosaedro con un vértice en cada polo, subdividido `subdivisions` veces: cada
+// triángulo se parte en cuatro y los puntos nuevos se proyectan sobre la esfera
+pub fn icosphere(radius: f32, subdivisions: u32) -> Mesh {
+    // Polos y dos anillos de cinco vértices a ±atan(1/2) de latitud, desfasados 36°
+    let ring_latitude = 0.5f32.atan();
+    let mut points = vec![Vec3::new(0.0, 1.0, 0.0)];
+    for i in 0..10 {
+        let longitude = i as f32 * PI / 5.0;
+        let latitude = if i % 2 == 0 { ring_latitude } else { -ring_latitude };
+        points.push(Vec3::new(
+            latitude.cos() * longitude.cos(),
+            latitude.sin(),
+            latitude.cos() * longitude.sin(),
+        ));
+    }
+    points.push(Vec3::new(0.0, -1.0, 0.0));
+
+    // Vértices 1, 3, 5, 7, 9 en el anillo superior; 2, 4, 6, 8, 10 en el inferior
+    let mut faces = Vec::with_capacity(20);
+    for i in 0..5 {
+        let upper = 1 + 2 * i;
+        let lower = upper + 1;
+        let next_upper = 1 + 2 * ((i + 1) % 5);
+        let next_lower = next_upper + 1;
+        faces.push([0, upper, next_upper]);
+        faces.push([upper, lower, next_upper]);
+        faces.push([next_upper, lower, next_lower]);
+        faces.push([lower, 11, next_lower]);
+    }
+
+    for _ in 0..subdivisions {
+        // Cada arista se parte una sola vez aunque la compartan dos triángulos
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, points: &mut Vec<Vec3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                points.push((points[a] + points[b]).normalize());
+                points.len() - 1
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces {
+            let ab = midpoint(a, b, &mut points);
+            let bc = midpoint(b, c, &mut points);
+            let ca = midpoint(c, a, &mut points);
+            subdivided.extend([[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]);
+        }
+        faces = subdivided;
+    }
+
+    let mut vertices = Vec::with_capacity(faces.len() * 3);
+    for face in faces {
+        let directions = face.map(|index| points[index]);
+        let poles = directions.map(|direction| direction.y.abs() > 0.9999);
+        let mut uvs = directions.map(equirect_uv);
+
+        // Triángulos que cruzan la costura (u = 0 = 1): llevar los u chicos al otro lado
+        let (min_u, max_u) = (0..3)
+            .filter(|&i| !poles[i])
+            .fold((1.0f32, 0.0f32), |(lo, hi), i| (lo.min(uvs[i].x), hi.max(uvs[i].x)));
+        if max_u - min_u > 0.5 {
+            for uv in &mut uvs {
+                if uv.x < 0.5 {
+                    uv.x += 1.0;
+                }
+            }
+        }
+
+        // En un polo la longitud no está definida: usar la de los otros dos vértices
+        for i in 0..3 {
+            if poles[i] {
+                uvs[i].x = (uvs[(i + 1) % 3].x + uvs[(i + 2) % 3].x) * 0.5;
+            }
+        }
+
+        push_triangle(&mut vertices, [
+            surface_vertex(radius, uvs[0]),
+            surface_vertex(radius, uvs[1]),
+            surface_vertex(radius, uvs[2]),
+        ]);
+    }
+
+    Mesh::from_triangles(vertices)
+}
+
+// Coordenadas equirectangulares de una dirección desde el centro. Es el mismo mapeo de
+// las esferas generadas (y de la antigua sphere.obj): v = 0 en el polo norte (+Y) y u
+// avanza de +Z hacia +X, con la costura en -X.
+pub fn equirect_uv(direction: Vec3) -> Vec2 {
+    let longitude = direction.z.atan2(direction.x);
+    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+    Vec2::new((0.5 - longitude / (2.0 * PI)).rem_euclid(1.0), 0.5 - latitude / PI)
+}
+
+// Dirección desde el centro para unas coordenadas equirectangulares (inversa de equirect_uv)
+pub fn equirect_direction(uv: Vec2) -> Vec3 {
+    let longitude = (0.5 - uv.x) * 2.0 * PI;
+    let latitude = (0.5 - uv.y) * PI;
+    Vec3::new(latitude.cos() * longitude.cos(), latitude.sin(), latitude.cos() * longitude.sin())
+}
+
+// Vértice de la superficie con normal suave y tangente exacta hacia +u. Como +v va hacia
+// el sur, la bitangente es -cross(normal, tangente) (w = -1)
+fn surface_vertex(radius: f32, uv: Vec2) -> Vertex {
+    let direction = equirect_direction(uv);
+    let longitude = (0.5 - uv.x) * 2.0 * PI;
+
+    let mut vertex = Vertex::new(direction * radius, direction, uv);
+    vertex.tangent = Vec4::new(longitude.sin(), 0.0, -longitude.cos(), -1.0);
+    vertex
+}
+
+// Agregar un triángulo con sus vértices en sentido antihorario vistos desde afuera,
+// como los que culling descarta cuando miran hacia atrás
+fn push_triangle(vertices: &mut Vec<Vertex>, [a, b, c]: [Vertex; 3]) {
+    let normal = (b.position - a.position).cross(&(c.position - a.position));
+    if normal.dot(&(a.position + b.position + c.position)) >= 0.0 {
+        vertices.extend([a, b, c]);
+    } else {
+        vertices.extend([a, c, b]);
+    }
+}
+
+// Mayor ángulo entre el centro de un triángulo (proyectado a la esfera) y sus vértices
+fn max_angle(mesh: &Mesh) -> f32 {
+    mesh
+        .triangles()
+        .map(|triangle| {
+            let center = (triangle[0].normal + triangle[1].normal + triangle[2].normal).normalize();
+            triangle
+                .iter()
+                .map(|vertex| center.dot(&vertex.normal).clamp(-1.0, 1.0).acos())
+                .fold(0.0, f32::max)
+        })
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cada cara tiene sus vértices en sentido antihorario vistos desde afuera
+    fn faces_point_outward(mesh: &Mesh) -> bool {
+        mesh.triangles().all(|[a, b, c]| {
+            let normal = (b.position - a.position).cross(&(c.position - a.position));
+            normal.dot(&(a.position + b.position + c.position)) > 0.0
+        })
+    }
+
+    // Cada vértice de la costura con u = 0 tiene una copia en la misma posición con u = 1
+    fn seam_is_duplicated(mesh: &Mesh) -> bool {
+        let seam: Vec<&Vertex> = mesh.vertices.iter().filter(|vertex| vertex.tex_coords.x == 0.0).collect();
+        !seam.is_empty()
+            && seam.iter().all(|start| {
+                mesh.vertices.iter().any(|end| {
+                    end.tex_coords.x == 1.0
+                        && end.tex_coords.y == start.tex_coords.y
+                        && (end.position - start.position).magnitude() < 1e-5
+                })
+            })
+    }
+
+    #[test]
+    fn icosphere_counts_per_level() {
+        for level in 0..4 {
+            let mesh = icosphere(1.0, level);
+            assert_eq!(mesh.triangle_count(), 20 * 4usize.pow(level));
+            assert_eq!(mesh.indices.len(), 3 * mesh.triangle_count());
+            // 10·4^n + 2 puntos, más 4 copias de cada polo (cada uno de sus 5 triángulos
+            // tiene su propia u) y una copia de los 3·2^n - 1 puntos de la costura
+            let points = 10 * 4usize.pow(level) + 2;
+            assert_eq!(mesh.vertices.len(), points + 2 * 4 + 3 * 2usize.pow(level) - 1);
+        }
+    }
+
+    #[test]
+    fn uv_sphere_counts() {
+        let (segments, rings) = (12, 6);
+        let mesh = uv_sphere(1.0, segments, rings);
+
+        // Dos triángulos por celda y uno por columna en cada polo
+        assert_eq!(mesh.triangle_count(), (2 * segments * (rings - 1)) as usize);
+        // Paralelos interiores con la columna u = 1 repetida, y un vértice de polo por columna
+        assert_eq!(mesh.vertices.len(), ((rings - 1) * (segments + 1) + 2 * segments) as usize);
+    }
+
+    #[test]
+    fn faces_wind_outward() {
+        for level in 0..4 {
+            assert!(faces_point_outward(&icosphere(1.0, level)), "icosfera nivel {}", level);
+        }
+        assert!(faces_point_outward(&uv_sphere(1.0, 12, 6)));
+    }
+
+    #[test]
+    fn seam_vertices_are_duplicated() {
+        assert!(seam_is_duplicated(&uv_sphere(1.0, 12, 6)));
+        assert!(seam_is_duplicated(&icosphere(1.0, 2)));
+    }
+
+    #[test]
+    fn lod_grows_with_screen_radius() {
+        let lods = SphereLods::new(SphereMesh::Ico, 1.0);
+        let coarsest = lods.levels.first().unwrap().mesh.triangle_count();
+        let finest = lods.levels.last().unwrap().mesh.triangle_count();
+
+        assert_eq!(lods.select(1.0).triangle_count(), coarsest);
+        assert_eq!(lods.select(1.0e6).triangle_count(), finest);
+
+        let mut previous = 0;
+        for radius in [1.0, 10.0, 50.0, 200.0, 1000.0, 5000.0] {
+            let count = lods.select(radius).triangle_count();
+            assert!(count >= previous, "radio {}: {} < {}", radius, count, previous);
+            previous = count;
+        }
+    }
+
+    #[test]
+    fn selected_lod_stays_within_tolerance() {
+        let lods = SphereLods::new(SphereMesh::Uv, 1.0);
+        for radius in [5.0, 40.0, 300.0] {
+            let selected = lods.select(radius);
+            let level = lods.levels.iter().find(|level| std::ptr::eq(&level.mesh, selected)).unwrap();
+            assert!(radius * (1.0 - level.max_angle.cos()) <= LOD_TOLERANCE, "radio {}", radius);
+        }
+    }
+}
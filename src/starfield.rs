@@ -0,0 +1,154 @@
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use std::f32::consts::PI;
+
+use crate::color::LinearColor;
+use crate::framebuffer::Framebuffer;
+
+// Estrella del catálogo: una dirección en el cielo (a distancia infinita) y su brillo
+struct Star {
+    direction: Vec3,
+    color: LinearColor,
+}
+
+// Fondo de estrellas generado con una semilla fija. Las estrellas son puntos en el infinito:
+// solo les afecta la rotación de la cámara, nunca su posición. La Vía Láctea opcional es
+// una banda de miles de puntos tenues concentrados alrededor de un plano galáctico.
+pub struct Starfield {
+    stars: Vec<Star>,
+}
+
+const STAR_COUNT: usize = 4000;
+const MILKY_WAY_COUNT: usize = 40000;
+const FAINTEST_MAGNITUDE: f32 = 6.5;
+
+impl Starfield {
+    pub fn new(seed: u64, milky_way: bool) -> Self {
+        let mut random = Random::new(seed);
+        let mut stars = Vec::with_capacity(STAR_COUNT + if milky_way { MILKY_WAY_COUNT } else { 0 });
+
+        for _ in 0..STAR_COUNT {
+            // Hay muchas más estrellas débiles que brillantes: N(m) crece ~10^(0.35 m)
+            let magnitude = (FAINTEST_MAGNITUDE + random.uniform().max(1e-6).log10() / 0.35).max(-1.0);
+            // Cada 5 magnitudes el flujo baja 100 veces
+            let brightness = 0.03 * 10f32.powf(-0.4 * (magnitude - FAINTEST_MAGNITUDE));
+            stars.push(Star {
+                direction: random.direction(),
+                color: star_tint(random.uniform()) * brightness,
+            });
+        }
+
+        if milky_way {
+            // Plano galáctico inclinado respecto al plano de las órbitas
+            let pole = Vec3::new(0.6, 0.78, 0.1).normalize();
+            let reference = pole.cross(&Vec3::new(0.0, 0.0, 1.0)).normalize();
+            let binormal = pole.cross(&reference);
+            let mut placed = 0;
+
+            while placed < MILKY_WAY_COUNT {
+                let direction = random.direction();
+                let latitude = direction.dot(&pole);
+                let longitude = direction.dot(&binormal).atan2(direction.dot(&reference));
+
+                // Banda gaussiana más ancha y densa hacia el núcleo, con nubes irregulares
+                let core = 0.5 + 0.5 * longitude.cos();
+                let width = 0.08 + 0.08 * core;
+                let clouds = 0.55 + 0.25 * (longitude * 7.0).sin() * (longitude * 3.0 + 1.0).cos()
+                    + 0.2 * (longitude * 17.0 + latitude * 40.0).sin();
+                let density = (-(latitude * latitude) / (2.0 * width * width)).exp() * clouds * (0.4 + 0.6 * core);
+
+                if random.uniform() < density {
+                    let warm = LinearColor::new(1.0, 0.9, 0.75);
+                    stars.push(Star {
+                        direction,
+                        color: warm * (0.01 + 0.015 * random.uniform()),
+                    });
+                    placed += 1;
+                }
+            }
+        }
+
+        Starfield { stars }
+    }
+
+    // Dibujar el fondo justo después de limpiar el framebuffer. Las direcciones se transforman
+    // con w = 0, así la traslación de la vista no las mueve. No se escribe profundidad:
+    // cualquier objeto de la escena queda delante.
+    pub fn draw(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        let view_projection = projection_matrix * view_matrix;
+        // Con SSAA la estrella se reparte en un área proporcional para verse igual al reducir
+        let radius = framebuffer.ssaa as i32;
+        let width = framebuffer.width as f32;
+        let height = framebuffer.height as f32;
+
+        for star in &self.stars {
+            let d = star.direction;
+            let clip = view_projection * Vec4::new(d.x, d.y, d.z, 0.0);
+            // Detrás de la cámara
+            if clip.w <= 0.0 {
+                continue;
+            }
+
+            let ndc = Vec4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0);
+            let screen = viewport_matrix * ndc;
+            if screen.x < -1.0 || screen.y < -1.0 || screen.x >= width || screen.y >= height {
+                continue;
+            }
+
+            splat(framebuffer, screen.x - 0.5, screen.y - 0.5, star.color, radius);
+        }
+    }
+}
+
+// Repartir la luz de la estrella entre los píxeles vecinos para que no parpadee al girar
+// la cámara. El núcleo es triangular de `radius` píxeles: con radio 1 es el reparto
+// bilineal entre 4 píxeles. Los pesos suman radius², lo mismo que promedia el SSAA.
+fn splat(framebuffer: &mut Framebuffer, x: f32, y: f32, color: LinearColor, radius: i32) {
+    let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+    let tent = |distance: f32| (1.0 - distance.abs() / radius as f32).max(0.0);
+
+    for py in (y0 - radius + 1)..=(y0 + radius) {
+        for px in (x0 - radius + 1)..=(x0 + radius) {
+            if px < 0 || py < 0 || px as usize >= framebuffer.width || py as usize >= framebuffer.height {
+                continue;
+            }
+            let weight = tent(px as f32 - x) * tent(py as f32 - y);
+            framebuffer.add(px as usize, py as usize, color * weight);
+        }
+    }
+}
+
+// Color aproximado según la temperatura: de rojizo (t = 0) a blanco azulado (t = 1)
+fn star_tint(t: f32) -> LinearColor {
+    let warm = LinearColor::new(1.0, 0.75, 0.5);
+    let cool = LinearColor::new(0.75, 0.85, 1.0);
+    warm.lerp(&cool, t)
+}
+
+// Generador pseudoaleatorio determinista (SplitMix64): el cielo es el mismo en cada ejecución
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    // Número en [0, 1)
+    fn uniform(&mut self) -> f32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // Dirección uniforme sobre la esfera
+    fn direction(&mut self) -> Vec3 {
+        let z = 2.0 * self.uniform() - 1.0;
+        let angle = 2.0 * PI * self.uniform();
+        let r = (1.0 - z * z).sqrt();
+        Vec3::new(r * angle.cos(), r * angle.sin(), z)
+    }
+}
@@ -0,0 +1,159 @@
+use nalgebra_glm::Vec2;
+use crate::color::{Color, LinearColor};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FilterMode {
+    Nearest,
+    Bilinear,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WrapMode {
+    Repeat,  // Repetir la imagen (longitud en mapas equirectangulares)
+    Clamp,   // Usar el texel del borde
+}
+
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<LinearColor>,  // ya convertidos de sRGB a lineal para filtrar correctamente
+    pub filter: FilterMode,
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+}
+
+impl Texture {
+    // Cargar una imagen PNG/JPEG. Por defecto filtra bilinealmente, repite en U
+    // y recorta en V, que es lo correcto para mapas equirectangulares de planetas.
+    pub fn load(path: &str) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        let pixels = image
+            .pixels()
+            .map(|p| LinearColor::from_srgb(Color::new(p[0], p[1], p[2])).with_alpha(p[3] as f32 / 255.0))
+            .collect();
+
+        Ok(Texture::from_pixels(width as usize, height as usize, pixels))
+    }
+
+    // Cargar una imagen de datos (mapas de normales): los valores se leen tal cual,
+    // en 0..1, sin la conversión de sRGB a lineal
+    pub fn load_data(path: &str) -> Result<Self, image::ImageError> {
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        let pixels = image
+            .pixels()
+            .map(|p| LinearColor::new(p[0] as f32 / 255.0, p[1] as f32 / 255.0, p[2] as f32 / 255.0).with_alpha(p[3] as f32 / 255.0))
+            .collect();
+
+        Ok(Texture::from_pixels(width as usize, height as usize, pixels))
+    }
+
+    // Textura generada en código, con los mismos modos por defecto que las cargadas
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<LinearColor>) -> Self {
+        Texture {
+            width,
+            height,
+            pixels,
+            filter: FilterMode::Bilinear,
+            wrap_u: WrapMode::Repeat,
+            wrap_v: WrapMode::Clamp,
+        }
+    }
+
+    // Muestrear la textura en coordenadas UV (0..1, v = 0 arriba)
+    pub fn sample(&self, uv: Vec2) -> LinearColor {
+        let x = uv.x * self.width as f32;
+        let y = uv.y * self.height as f32;
+
+        match self.filter {
+            FilterMode::Nearest => self.texel(x.floor() as i32, y.floor() as i32),
+            FilterMode::Bilinear => {
+                // Los centros de los texels están en (i + 0.5)
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = x.floor();
+                let y0 = y.floor();
+                let tx = x - x0;
+                let ty = y - y0;
+                let (x0, y0) = (x0 as i32, y0 as i32);
+
+                let top = self.texel(x0, y0).lerp(&self.texel(x0 + 1, y0), tx);
+                let bottom = self.texel(x0, y0 + 1).lerp(&self.texel(x0 + 1, y0 + 1), tx);
+                top.lerp(&bottom, ty)
+            }
+        }
+    }
+
+    fn texel(&self, x: i32, y: i32) -> LinearColor {
+        let x = wrap(x, self.width, self.wrap_u);
+        let y = wrap(y, self.height, self.wrap_v);
+        self.pixels[y * self.width + x]
+    }
+}
+
+fn wrap(coord: i32, size: usize, mode: WrapMode) -> usize {
+    match mode {
+        WrapMode::Repeat => coord.rem_euclid(size as i32) as usize,
+        WrapMode::Clamp => coord.clamp(0, size as i32 - 1) as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Texels 0 1 / 2 3 (fila de arriba, fila de abajo), guardados en el canal rojo
+    fn fixture(filter: FilterMode, wrap_u: WrapMode) -> Texture {
+        let pixels = [0.0, 1.0, 2.0, 3.0].map(|value| LinearColor::new(value, 0.0, 0.0)).to_vec();
+        Texture { filter, wrap_u, ..Texture::from_pixels(2, 2, pixels) }
+    }
+
+    fn red(texture: &Texture, u: f32, v: f32) -> f32 {
+        texture.sample(Vec2::new(u, v)).r
+    }
+
+    #[test]
+    fn nearest_returns_exact_texels() {
+        let texture = fixture(FilterMode::Nearest, WrapMode::Repeat);
+        assert_eq!(red(&texture, 0.25, 0.25), 0.0);
+        assert_eq!(red(&texture, 0.75, 0.25), 1.0);
+        assert_eq!(red(&texture, 0.25, 0.75), 2.0);
+        assert_eq!(red(&texture, 0.99, 0.99), 3.0);
+    }
+
+    #[test]
+    fn bilinear_blends_between_texel_centers() {
+        let texture = fixture(FilterMode::Bilinear, WrapMode::Clamp);
+        assert_eq!(red(&texture, 0.25, 0.25), 0.0);  // centro de un texel: sin mezcla
+        assert_eq!(red(&texture, 0.5, 0.25), 0.5);
+        assert_eq!(red(&texture, 0.25, 0.5), 1.0);
+        assert_eq!(red(&texture, 0.5, 0.5), 1.5);
+    }
+
+    #[test]
+    fn repeat_wraps_around_in_u() {
+        let texture = fixture(FilterMode::Nearest, WrapMode::Repeat);
+        assert_eq!(red(&texture, -0.25, 0.25), 1.0);
+        assert_eq!(red(&texture, 1.25, 0.25), 0.0);
+
+        // En el borde el filtro bilineal mezcla con el texel del otro lado
+        let texture = fixture(FilterMode::Bilinear, WrapMode::Repeat);
+        assert_eq!(red(&texture, 0.0, 0.25), 0.5);
+    }
+
+    #[test]
+    fn clamp_uses_the_edge_texel() {
+        let texture = fixture(FilterMode::Nearest, WrapMode::Clamp);
+        assert_eq!(red(&texture, -0.25, 0.25), 0.0);
+        assert_eq!(red(&texture, 1.25, 0.25), 1.0);
+
+        let texture = fixture(FilterMode::Bilinear, WrapMode::Clamp);
+        assert_eq!(red(&texture, 0.0, 0.25), 0.0);
+
+        // V recorta por defecto (mapas equirectangulares): más allá del polo sur, la última fila
+        assert_eq!(red(&texture, 0.25, 1.5), 2.0);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::color::LinearColor;
+
+// Operador para llevar colores HDR al rango [0, 1] de la pantalla
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ToneMapping {
+    Reinhard,
+    Aces,
+}
+
+impl ToneMapping {
+    pub fn apply(&self, color: LinearColor) -> LinearColor {
+        match self {
+            // Reinhard por canal: x / (1 + x)
+            ToneMapping::Reinhard => LinearColor::new(
+                color.r / (1.0 + color.r),
+                color.g / (1.0 + color.g),
+                color.b / (1.0 + color.b),
+            ),
+            // Aproximación de la curva filmica ACES (Narkowicz 2015)
+            ToneMapping::Aces => LinearColor::new(aces(color.r), aces(color.g), aces(color.b)),
+        }
+    }
+}
+
+fn aces(x: f32) -> f32 {
+    let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+    ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATORS: [ToneMapping; 2] = [ToneMapping::Reinhard, ToneMapping::Aces];
+
+    fn map(operator: ToneMapping, x: f32) -> f32 {
+        operator.apply(LinearColor::new(x, x, x)).r
+    }
+
+    #[test]
+    fn operators_are_monotonic() {
+        for operator in OPERATORS {
+            let mut previous = map(operator, 0.0);
+            for step in 1..=2000 {
+                let value = map(operator, step as f32 * 0.01);
+                assert!(value >= previous, "{:?} baja en {}", operator, step as f32 * 0.01);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn operators_map_black_to_black_and_stay_in_range() {
+        for operator in OPERATORS {
+            assert!(map(operator, 0.0).abs() < 1e-6, "{:?}", operator);
+            for x in [0.1, 1.0, 10.0, 1000.0, 1.0e6] {
+                let value = map(operator, x);
+                assert!((0.0..=1.0).contains(&value), "{:?}({}) = {}", operator, x, value);
+            }
+        }
+    }
+
+    #[test]
+    fn operators_approach_white_for_bright_input() {
+        assert!((map(ToneMapping::Reinhard, 1.0) - 0.5).abs() < 1e-6);
+        assert!(map(ToneMapping::Reinhard, 1.0e4) > 0.999);
+        assert!(map(ToneMapping::Aces, 100.0) > 0.99);
+        assert_eq!(map(ToneMapping::Aces, 1.0e6), 1.0);
+    }
+}
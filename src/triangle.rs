@@ -1,76 +1,134 @@
-use nalgebra_glm::{Vec3, dot};
-use crate::fragment::Fragment;
+use nalgebra_glm::{Vec2, Vec3};
+use crate::fragment::{Fragment, FragmentSink};
 use crate::vertex::Vertex;
-use crate::color::Color;
+use crate::rasterizer::Tile;
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, sun_world_position: &Vec3, is_sun: bool) -> Vec<Fragment> {
-  let mut fragments = Vec::new();
-  let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CullMode {
+  None,   // Dibujar ambas caras (mallas abiertas como la nave)
+  Back,   // Descartar triángulos que dan la espalda a la cámara
+}
 
-  let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
+impl CullMode {
+  // El área con signo de edge_function es positiva para triángulos frontales
+  // (antihorario en NDC; el viewport invierte Y, por eso el signo queda positivo en pantalla)
+  fn culls(&self, signed_area: f32) -> bool {
+    match self {
+      CullMode::None => false,
+      CullMode::Back => signed_area < 0.0,
+    }
+  }
+}
+
+// Rasteriza el triángulo dentro del tile y entrega cada fragment visible al sink
+pub fn triangle<S: FragmentSink>(
+  v1: &Vertex,
+  v2: &Vertex,
+  v3: &Vertex,
+  cull_mode: CullMode,
+  tile: &Tile,
+  sink: &mut S,
+) {
+  let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
 
   let triangle_area = edge_function(&a, &b, &c);
 
+  // Triángulos degenerados o descartados por culling no generan fragments
+  if triangle_area == 0.0 || cull_mode.culls(triangle_area) {
+    return;
+  }
+
+  let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c, tile);
+
+  // La profundidad es un plano en pantalla: su gradiente sirve para cada muestra MSAA
+  // (triangle_area es el determinante del triángulo con el signo invertido)
+  let (ab, ac) = (b - a, c - a);
+  let depth_gradient = Vec2::new(
+    (ab.y * ac.z - ac.y * ab.z) / triangle_area,
+    (ac.x * ab.z - ab.x * ac.z) / triangle_area,
+  );
+
   // Iterate over each pixel in the bounding box
   for y in min_y..=max_y {
     for x in min_x..=max_x {
-      let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
-
-      // Calculate barycentric coordinates
-      let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
-
-      // Check if the point is inside the triangle
-      if w1 >= 0.0 && w1 <= 1.0 && 
-         w2 >= 0.0 && w2 <= 1.0 &&
-         w3 >= 0.0 && w3 <= 1.0 {
-        
-        // Interpolar el color de los vértices
-        let interpolated_color = Color::new(
-          (v1.color.r as f32 * w1 + v2.color.r as f32 * w2 + v3.color.r as f32 * w3) as u8,
-          (v1.color.g as f32 * w1 + v2.color.g as f32 * w2 + v3.color.g as f32 * w3) as u8,
-          (v1.color.b as f32 * w1 + v2.color.b as f32 * w2 + v3.color.b as f32 * w3) as u8,
-        );
-        
-        // Si es el sol, no aplicar iluminación (es emisivo)
-        let lit_color = if is_sun {
-          interpolated_color  // El sol brilla por sí mismo
-        } else {
-          // Interpolar la normal EN ESPACIO MUNDIAL
-          let normal = (v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3).normalize();
-          
-          // Interpolar la posición EN ESPACIO MUNDIAL
-          let world_position = v1.position * w1 + v2.position * w2 + v3.position * w3;
-          
-          // Calcular vector desde el fragmento HACIA el sol (fuente de luz)
-          let to_light = (*sun_world_position - world_position).normalize();
-          
-          // Dot product: positivo cuando la normal apunta hacia la luz
-          let intensity = dot(&normal, &to_light).max(0.0);
-          
-          // Ambient + diffuse lighting
-          let ambient = 0.2;
-          let diffuse = 0.8 * intensity;
-          let total_light = ambient + diffuse;
-          
-          interpolated_color * total_light
-        };
-
-        // Interpolate depth
-        let depth = a.z * w1 + b.z * w2 + c.z * w3;
-
-        fragments.push(Fragment::new(x as f32, y as f32, lit_color, depth));
+      // Cobertura y z-test en cada muestra del píxel (una sola, en el centro, sin MSAA)
+      let mut coverage = 0u32;
+      let mut covered = 0;
+      let mut weights = Vec3::new(0.0, 0.0, 0.0);
+
+      for (sample, offset) in sink.sample_offsets().iter().enumerate() {
+        let point = Vec3::new(x as f32 + offset.x, y as f32 + offset.y, 0.0);
+
+        // Calculate barycentric coordinates
+        let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
+
+        // Check if the point is inside the triangle
+        let inside = 0.0..=1.0;
+        if inside.contains(&w1) && inside.contains(&w2) && inside.contains(&w3) {
+
+          // Interpolate depth (z de pantalla ya es lineal en espacio de pantalla)
+          let depth = a.z * w1 + b.z * w2 + c.z * w3;
+
+          // Early-z: no interpolar ni sombrear fragments que ya están ocultos
+          if sink.test_depth(x as usize, y as usize, sample, depth) {
+            coverage |= 1 << sample;
+            covered += 1;
+            weights += Vec3::new(w1, w2, w3);
+          }
+        }
       }
+
+      if coverage == 0 {
+        continue;
+      }
+
+      // Los atributos se evalúan una vez por píxel, en el centroide de las muestras
+      // visibles: así nunca se extrapolan fuera del triángulo
+      let (w1, w2, w3) = (weights.x / covered as f32, weights.y / covered as f32, weights.z / covered as f32);
+      let centroid = Vec2::new(
+        a.x * w1 + b.x * w2 + c.x * w3 - x as f32,
+        a.y * w1 + b.y * w2 + c.y * w3 - y as f32,
+      );
+      let depth = a.z * w1 + b.z * w2 + c.z * w3 + depth_gradient.dot(&(Vec2::new(0.5, 0.5) - centroid));
+
+      // Pesos con corrección de perspectiva: los atributos varían linealmente en
+      // espacio de clip, así que se interpolan attr/w y se divide por la suma de 1/w
+      let (p1, p2, p3) = (w1 * v1.inv_w, w2 * v2.inv_w, w3 * v3.inv_w);
+      let inv_w_sum = p1 + p2 + p3;
+      let (p1, p2, p3) = (p1 / inv_w_sum, p2 / inv_w_sum, p3 / inv_w_sum);
+
+      let tex_coords = v1.tex_coords * p1 + v2.tex_coords * p2 + v3.tex_coords * p3;
+      let object_position = v1.object_position * p1 + v2.object_position * p2 + v3.object_position * p3;
+      
+      // Interpolar la normal EN ESPACIO MUNDIAL
+      let normal = (v1.transformed_normal * p1 + v2.transformed_normal * p2 + v3.transformed_normal * p3).normalize();
+
+      // Tangente para los mapas de normales (se ortogonaliza al usarla)
+      let tangent = v1.transformed_tangent * p1 + v2.transformed_tangent * p2 + v3.transformed_tangent * p3;
+
+      // Interpolar la posición EN ESPACIO MUNDIAL
+      let world_position = v1.position * p1 + v2.position * p2 + v3.position * p3;
+
+      // La iluminación la calcula el FragmentShader de cada material
+      let mut fragment = Fragment::new(x as f32, y as f32, depth);
+      fragment.depth_gradient = depth_gradient;
+      fragment.coverage = coverage;
+      fragment.tex_coords = tex_coords;
+      fragment.object_position = object_position;
+      fragment.world_position = world_position;
+      fragment.normal = normal;
+      fragment.tangent = tangent;
+      sink.write(fragment);
     }
   }
-
-  fragments
 }
 
-fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
-    let min_x = v1.x.min(v2.x).min(v3.x).floor() as i32;
-    let min_y = v1.y.min(v2.y).min(v3.y).floor() as i32;
-    let max_x = v1.x.max(v2.x).max(v3.x).ceil() as i32;
-    let max_y = v1.y.max(v2.y).max(v3.y).ceil() as i32;
+// Bounding box del triángulo recortado a los límites del tile
+fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3, tile: &Tile) -> (i32, i32, i32, i32) {
+    let min_x = (v1.x.min(v2.x).min(v3.x).floor() as i32).max(tile.min_x);
+    let min_y = (v1.y.min(v2.y).min(v3.y).floor() as i32).max(tile.min_y);
+    let max_x = (v1.x.max(v2.x).max(v3.x).ceil() as i32).min(tile.max_x);
+    let max_y = (v1.y.max(v2.y).max(v3.y).ceil() as i32).min(tile.max_y);
 
     (min_x, min_y, max_x, max_y)
 }
@@ -1,4 +1,4 @@
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::{Vec2, Vec3, Vec4};
 use crate::color::Color;
 
 #[derive(Clone, Debug)]
@@ -6,9 +6,14 @@ pub struct Vertex {
   pub position: Vec3,
   pub normal: Vec3,
   pub tex_coords: Vec2,
+  pub tangent: Vec4,  // xyz = dirección de +u en la superficie, w = ±1 orientación de la bitangente
   pub color: Color,
   pub transformed_position: Vec3,
   pub transformed_normal: Vec3,
+  pub transformed_tangent: Vec4,
+  pub clip_position: Vec4,
+  pub inv_w: f32,
+  pub object_position: Vec3,
 }
 
 impl Vertex {
@@ -17,27 +22,34 @@ impl Vertex {
       position,
       normal,
       tex_coords,
+      tangent: default_tangent(),
       color: Color::black(),
       transformed_position: position,
       transformed_normal: normal,
+      transformed_tangent: default_tangent(),
+      clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
+      inv_w: 1.0,
+      object_position: position,
     }
   }
 
-  pub fn new_with_color(position: Vec3, color: Color) -> Self {
+  // Interpolar todos los atributos entre dos vértices (usado al recortar triángulos)
+  pub fn lerp(&self, other: &Vertex, t: f32) -> Vertex {
+    let clip_position = self.clip_position + (other.clip_position - self.clip_position) * t;
     Vertex {
-      position,
-      normal: Vec3::new(0.0, 0.0, 0.0),
-      tex_coords: Vec2::new(0.0, 0.0),
-      color,
-      transformed_position: Vec3::new(0.0, 0.0, 0.0),
-      transformed_normal: Vec3::new(0.0, 0.0, 0.0),
+      position: self.position + (other.position - self.position) * t,
+      normal: self.normal + (other.normal - self.normal) * t,
+      tex_coords: self.tex_coords + (other.tex_coords - self.tex_coords) * t,
+      tangent: self.tangent + (other.tangent - self.tangent) * t,
+      color: self.color * (1.0 - t) + other.color * t,
+      transformed_position: self.transformed_position + (other.transformed_position - self.transformed_position) * t,
+      transformed_normal: self.transformed_normal + (other.transformed_normal - self.transformed_normal) * t,
+      transformed_tangent: self.transformed_tangent + (other.transformed_tangent - self.transformed_tangent) * t,
+      clip_position,
+      inv_w: 1.0 / clip_position.w,
+      object_position: self.object_position + (other.object_position - self.object_position) * t,
     }
   }
-
-  pub fn set_transformed(&mut self, position: Vec3, normal: Vec3) {
-    self.transformed_position = position;
-    self.transformed_normal = normal;
-  }
 }
 
 impl Default for Vertex {
@@ -46,9 +58,19 @@ impl Default for Vertex {
       position: Vec3::new(0.0, 0.0, 0.0),
       normal: Vec3::new(0.0, 1.0, 0.0),
       tex_coords: Vec2::new(0.0, 0.0),
+      tangent: default_tangent(),
       color: Color::black(),
       transformed_position: Vec3::new(0.0, 0.0, 0.0),
       transformed_normal: Vec3::new(0.0, 1.0, 0.0),
+      transformed_tangent: default_tangent(),
+      clip_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
+      inv_w: 1.0,
+      object_position: Vec3::new(0.0, 0.0, 0.0),
     }
   }
-}
\ No newline at end of file
+}
+
+// Tangente de los vértices sin coordenadas de textura (se reemplaza al cargar el OBJ)
+fn default_tangent() -> Vec4 {
+  Vec4::new(1.0, 0.0, 0.0, 1.0)
+}